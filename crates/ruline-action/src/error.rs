@@ -1,7 +1,15 @@
+use ruline_field::FieldType;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum ActionError {
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
+
+    #[error("Variable `{variable}` is of type `{expected}`, cannot set a `{received}`")]
+    VariableTypeMismatch {
+        variable: String,
+        expected: FieldType,
+        received: FieldType,
+    },
 }
@@ -1,8 +1,8 @@
 use anyhow::Result;
 use ruline_context::Context;
-use ruline_field::{Field, FieldDefinition};
+use ruline_field::{Field, FieldDefinition, FieldType, FunctionRegistry};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 
 mod error;
 pub use error::ActionError;
@@ -16,23 +16,33 @@ pub enum ActionDefinition {
     },
 }
 
+/// Sets a variable. Once given an id with `with_id`, every run publishes
+/// under it an output with the `variable` set and the `value` written, e.g.
+/// `{ "variable": "rate", "value": 0.1 }`.
 #[derive(Debug)]
 pub struct Action {
+    pub id: Option<String>,
     pub definition: ActionDefinition,
     pub dependencies: Vec<String>,
+    value: Field,
+    variable_type: FieldType,
 }
 
 impl TryFrom<ActionDefinition> for Action {
     type Error = ActionError;
 
     fn try_from(definition: ActionDefinition) -> Result<Self, Self::Error> {
-        let dependencies = match &definition {
-            ActionDefinition::SetVariable { value, .. } => Field::from(value).dependencies(),
+        let value = match &definition {
+            ActionDefinition::SetVariable { value, .. } => Field::from(value),
         };
+        let dependencies = value.dependencies();
 
         Ok(Action {
+            id: None,
             definition,
             dependencies,
+            value,
+            variable_type: FieldType::Any,
         })
     }
 }
@@ -48,11 +58,64 @@ impl TryFrom<Value> for Action {
 }
 
 impl Action {
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_owned());
+        self
+    }
+
+    /// Rebuilds the value resolving the custom functions it calls in
+    /// `functions`.
+    pub fn with_functions(mut self, functions: &FunctionRegistry) -> Self {
+        self.value = match &self.definition {
+            ActionDefinition::SetVariable { value, .. } => Field::with_functions(value, functions),
+        };
+        self
+    }
+
+    /// Declares the type of the variable set, so writing a value of another
+    /// type fails.
+    pub fn with_variable_type(mut self, variable_type: FieldType) -> Self {
+        self.variable_type = variable_type;
+        self
+    }
+
     pub fn process(&self, ctx: &Context) -> Result<()> {
+        self.apply(ctx, self.value.process(ctx)?)
+    }
+
+    pub async fn process_async(&self, ctx: &Context) -> Result<()> {
+        self.apply(ctx, self.value.process_async(ctx).await?)
+    }
+
+    /// Checks that `value` can be written to the variable set.
+    pub fn check(&self, value: &Value) -> Result<(), ActionError> {
+        let received = FieldType::of(value);
+        match self.variable_type {
+            FieldType::Any => Ok(()),
+            expected if expected == received => Ok(()),
+            expected => match &self.definition {
+                ActionDefinition::SetVariable { variable, .. } => {
+                    Err(ActionError::VariableTypeMismatch {
+                        variable: variable.to_owned(),
+                        expected,
+                        received,
+                    })
+                }
+            },
+        }
+    }
+
+    fn apply(&self, ctx: &Context, value: Value) -> Result<()> {
+        self.check(&value)?;
         match &self.definition {
-            ActionDefinition::SetVariable { variable, value } => {
-                let field = Field::from(value);
-                ctx.set_variable(variable.to_owned(), field.process(ctx)?);
+            ActionDefinition::SetVariable { variable, .. } => {
+                if let Some(id) = &self.id {
+                    ctx.set_output(
+                        id.to_owned(),
+                        json!({ "variable": variable, "value": value }),
+                    );
+                }
+                ctx.set_variable(variable.to_owned(), value);
             }
         }
 
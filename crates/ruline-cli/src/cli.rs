@@ -0,0 +1,84 @@
+use clap::{value_parser, Arg, Command};
+
+use crate::Format;
+
+fn spec(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("definition")
+                .short('d')
+                .long("definition")
+                .required(true)
+                .help("JSON file with the workflow components"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .required(true)
+                .help("JSON file with the workflow output spec"),
+        )
+        .arg(
+            Arg::new("variables")
+                .long("variables")
+                .help("JSON file with the variable declarations"),
+        )
+        .arg(
+            Arg::new("schema")
+                .long("schema")
+                .help("JSON file with the input schema"),
+        )
+}
+
+pub fn command() -> Command {
+    Command::new("ruline")
+        .about("Run and debug ruline workflows locally")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            spec(Command::new("run").about("Process every input record, writing results as JSONL"))
+                .arg(
+                    Arg::new("input")
+                        .short('i')
+                        .long("input")
+                        .default_value("-")
+                        .help("Input file, or `-` for stdin"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .short('f')
+                        .long("format")
+                        .value_parser(value_parser!(Format))
+                        .help("Input format, guessed from the file extension by default"),
+                )
+                .arg(
+                    Arg::new("workers")
+                        .short('w')
+                        .long("workers")
+                        .value_parser(value_parser!(usize))
+                        .help("Worker threads, one per CPU by default"),
+                ),
+        )
+        .subcommand(spec(
+            Command::new("validate").about("Check the workflow for cycles and type errors"),
+        ))
+        .subcommand(
+            spec(Command::new("graph").about("Print the component graph")).arg(
+                Arg::new("format")
+                    .short('f')
+                    .long("format")
+                    .value_parser(["dot", "mermaid"])
+                    .default_value("dot")
+                    .help("Graphviz DOT or Mermaid flowchart"),
+            ),
+        )
+        .subcommand(
+            spec(Command::new("test").about("Run test cases against the workflow")).arg(
+                Arg::new("cases")
+                    .short('c')
+                    .long("cases")
+                    .required(true)
+                    .help("JSON or JSONL file of `{ name, input, expected | error }` cases"),
+            ),
+        )
+}
@@ -0,0 +1,159 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    thread,
+};
+
+use anyhow::Result;
+use clap::ArgMatches;
+use serde_json::{json, Value};
+
+use crate::{input::read_records, spec, CliError, Format};
+
+/// Records given to each worker per chunk by `run`.
+const CHUNK_PER_WORKER: usize = 64;
+
+fn write_line(stdout: &mut impl Write, value: &Value) -> Result<()> {
+    serde_json::to_writer(&mut *stdout, value)?;
+    writeln!(stdout)?;
+    Ok(())
+}
+
+pub fn run(matches: &ArgMatches, stdin: impl BufRead, stdout: &mut impl Write) -> Result<()> {
+    let workflow = spec::load(matches)?;
+    let path = matches.get_one::<String>("input").unwrap();
+    let format = matches
+        .get_one::<Format>("format")
+        .copied()
+        .or_else(|| Format::from_path(path))
+        .unwrap_or(Format::Jsonl);
+
+    let mut records = match path.as_str() {
+        "-" => read_records(stdin, format),
+        path => {
+            let file = File::open(path).map_err(|source| CliError::Read {
+                path: path.to_owned(),
+                source,
+            })?;
+            read_records(BufReader::new(file), format)
+        }
+    };
+
+    let workers = match matches.get_one::<usize>("workers") {
+        Some(workers) => *workers,
+        None => thread::available_parallelism().map_or(1, |workers| workers.get()),
+    };
+
+    // Records are processed a chunk at a time, so only one chunk is held in
+    // memory and its results are written as soon as it finishes.
+    let size = workers.max(1) * CHUNK_PER_WORKER;
+    loop {
+        let mut chunk = Vec::with_capacity(size);
+        let mut failed = None;
+        for record in records.by_ref().take(size) {
+            match record {
+                Ok(record) => chunk.push(record),
+                Err(err) => {
+                    failed = Some(err);
+                    break;
+                }
+            }
+        }
+
+        let last = failed.is_some() || chunk.len() < size;
+        for result in workflow.process_batch_with_workers(chunk, workers) {
+            let line = result.unwrap_or_else(|err| json!({ "error": err.to_string() }));
+            write_line(stdout, &line)?;
+        }
+        stdout.flush()?;
+
+        if let Some(err) = failed {
+            return Err(err);
+        }
+        if last {
+            return Ok(());
+        }
+    }
+}
+
+pub fn validate(matches: &ArgMatches, stdout: &mut impl Write) -> Result<()> {
+    let workflow = spec::load(matches)?;
+    workflow.validate()?;
+    for warning in workflow.lint() {
+        writeln!(stdout, "warning: {}", warning)?;
+    }
+    writeln!(stdout, "ok")?;
+    Ok(())
+}
+
+pub fn graph(matches: &ArgMatches, stdout: &mut impl Write) -> Result<()> {
+    let workflow = spec::load(matches)?;
+    let graph = match matches.get_one::<String>("format").map(String::as_str) {
+        Some("mermaid") => workflow.to_mermaid(),
+        _ => workflow.to_dot(),
+    };
+
+    stdout.write_all(graph.as_bytes())?;
+    Ok(())
+}
+
+/// Runs every case and reports each outcome, returning whether all passed.
+pub fn test(matches: &ArgMatches, stdout: &mut impl Write) -> Result<bool> {
+    let workflow = spec::load(matches)?;
+    let path = matches.get_one::<String>("cases").unwrap();
+    let format = Format::from_path(path).unwrap_or(Format::Json);
+    let file = File::open(path).map_err(|source| CliError::Read {
+        path: path.to_owned(),
+        source,
+    })?;
+    let mut passed = 0;
+    let mut failed = 0;
+    for (index, case) in read_records(BufReader::new(file), format).enumerate() {
+        let case = case?;
+        let name = case["name"]
+            .as_str()
+            .map_or_else(|| format!("#{}", index + 1), ToOwned::to_owned);
+        let input = case.get("input").cloned().unwrap_or(json!({}));
+
+        let expected = case.get("expected");
+        let expected_error = case.get("error").and_then(Value::as_str);
+        if expected.is_none() && expected_error.is_none() {
+            return Err(CliError::CaseInvalid(name).into());
+        }
+
+        let failure = match workflow.process(input) {
+            Ok(result) => match expected {
+                Some(expected) if &result == expected => None,
+                Some(expected) => Some(format!("expected {}, got {}", expected, result)),
+                None => Some(format!(
+                    "expected error {:?}, got {}",
+                    expected_error.unwrap_or_default(),
+                    result
+                )),
+            },
+            Err(err) => match expected_error {
+                Some(expected) if err.to_string() == expected => None,
+                Some(expected) => Some(format!(
+                    "expected error {:?}, got error {:?}",
+                    expected,
+                    err.to_string()
+                )),
+                None => Some(format!("unexpected error: {}", err)),
+            },
+        };
+
+        match failure {
+            None => {
+                passed += 1;
+                writeln!(stdout, "ok {}", name)?;
+            }
+            Some(failure) => {
+                failed += 1;
+                writeln!(stdout, "FAILED {}: {}", name, failure)?;
+            }
+        }
+    }
+
+    writeln!(stdout, "{} passed, {} failed", passed, failed)?;
+    Ok(failed == 0)
+}
@@ -0,0 +1,32 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("Cannot read `{path}`: {source}")]
+    Read {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("Cannot parse `{path}`: {source}")]
+    Parse {
+        path: String,
+        source: serde_json::Error,
+    },
+    #[error("Unknown input format `{0}`, expected `json`, `jsonl` or `csv`")]
+    UnknownFormat(String),
+    #[error("Line {line}: {source}")]
+    Record {
+        line: usize,
+        source: serde_json::Error,
+    },
+    #[error("Line {line}: expected {expected} columns, got {received}")]
+    ColumnsMismatch {
+        line: usize,
+        expected: usize,
+        received: usize,
+    },
+    #[error("Line {0}: unterminated quoted field")]
+    UnterminatedQuote(usize),
+    #[error("Test case {0} must have an `expected` or an `error` field")]
+    CaseInvalid(String),
+}
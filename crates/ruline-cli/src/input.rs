@@ -0,0 +1,214 @@
+use std::{io::BufRead, iter, path::Path, str::FromStr};
+
+use anyhow::Result;
+use csv_core::ReadFieldResult;
+use serde_json::{Map, Value};
+
+use crate::CliError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A single record, or an array of records.
+    Json,
+    /// One record per line.
+    Jsonl,
+    /// A header row followed by one record per row.
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = CliError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "json" => Ok(Format::Json),
+            "jsonl" | "ndjson" => Ok(Format::Jsonl),
+            "csv" => Ok(Format::Csv),
+            format => Err(CliError::UnknownFormat(format.to_owned())),
+        }
+    }
+}
+
+impl Format {
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = Path::new(path).extension()?.to_str()?;
+        extension.parse().ok()
+    }
+}
+
+/// The records of an input, read as they are consumed.
+pub type Records<'a> = Box<dyn Iterator<Item = Result<Value>> + 'a>;
+
+/// Reads the records of `reader` lazily, except for JSON whose single
+/// document is parsed whole. A record that cannot be read yields an error.
+pub fn read_records<'a>(reader: impl BufRead + 'a, format: Format) -> Records<'a> {
+    match format {
+        Format::Json => match serde_json::from_reader(reader) {
+            Ok(Value::Array(records)) => Box::new(records.into_iter().map(Ok)),
+            Ok(record) => Box::new(iter::once(Ok(record))),
+            Err(source) => Box::new(iter::once(Err(CliError::Record {
+                line: source.line(),
+                source,
+            }
+            .into()))),
+        },
+        Format::Jsonl => Box::new(reader.lines().enumerate().filter_map(|(index, line)| {
+            let line = match line {
+                Ok(line) if line.trim().is_empty() => return None,
+                Ok(line) => line,
+                Err(err) => return Some(Err(err.into())),
+            };
+
+            let record = serde_json::from_str(&line).map_err(|source| CliError::Record {
+                line: index + 1,
+                source,
+            });
+            Some(record.map_err(Into::into))
+        })),
+        Format::Csv => Box::new(CsvRecords {
+            rows: CsvReader::new(reader),
+            header: None,
+        }),
+    }
+}
+
+/// Quoted cells are strings. Others holding a JSON number, boolean or null
+/// keep that type, empty ones become null and everything else is a string.
+fn cell(value: String, quoted: bool) -> Value {
+    if quoted {
+        return Value::String(value);
+    }
+    if value.is_empty() {
+        return Value::Null;
+    }
+
+    match serde_json::from_str(&value) {
+        Ok(parsed @ (Value::Number(_) | Value::Bool(_) | Value::Null)) => parsed,
+        _ => Value::String(value),
+    }
+}
+
+/// Records of a CSV input, one per row after the header.
+struct CsvRecords<R> {
+    rows: CsvReader<R>,
+    header: Option<Vec<String>>,
+}
+
+impl<R: BufRead> CsvRecords<R> {
+    fn read_record(&mut self) -> Result<Option<Value>> {
+        let header = match &mut self.header {
+            Some(header) => header,
+            None => {
+                let Some((_, header)) = self.rows.read_row()? else {
+                    return Ok(None);
+                };
+                // The parser only strips a byte order mark read in a single chunk.
+                self.header.insert(
+                    header
+                        .into_iter()
+                        .map(|(name, _)| name.trim_start_matches('\u{feff}').to_owned())
+                        .collect(),
+                )
+            }
+        };
+
+        let Some((line, row)) = self.rows.read_row()? else {
+            return Ok(None);
+        };
+        if row.len() != header.len() {
+            return Err(CliError::ColumnsMismatch {
+                line,
+                expected: header.len(),
+                received: row.len(),
+            }
+            .into());
+        }
+
+        let record = header
+            .iter()
+            .cloned()
+            .zip(row.into_iter().map(|(value, quoted)| cell(value, quoted)))
+            .collect::<Map<_, _>>();
+        Ok(Some(Value::Object(record)))
+    }
+}
+
+impl<R: BufRead> Iterator for CsvRecords<R> {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Fields of a CSV row, each along with whether it was quoted.
+type Row = Vec<(String, bool)>;
+
+/// Reads RFC 4180 rows one field at a time, keeping track of the fields that
+/// were quoted, which `csv_core` unescapes but does not report.
+struct CsvReader<R> {
+    input: R,
+    parser: csv_core::Reader,
+    line: usize,
+}
+
+impl<R: BufRead> CsvReader<R> {
+    fn new(input: R) -> Self {
+        Self {
+            input,
+            parser: csv_core::Reader::new(),
+            line: 1,
+        }
+    }
+
+    /// Returns the next row, tagged with the line it starts on, with each
+    /// field and whether it was quoted.
+    fn read_row(&mut self) -> Result<Option<(usize, Row)>> {
+        let mut row = Vec::new();
+        let mut row_line = None;
+        let mut field = Vec::new();
+        let mut quoted = None;
+        let mut quotes = 0;
+        let mut output = [0; 4096];
+
+        loop {
+            let input = self.input.fill_buf()?;
+            let (result, read, written) = self.parser.read_field(input, &mut output);
+
+            // The first field of a row also consumes the previous terminator.
+            for &byte in &input[..read] {
+                match byte {
+                    b'\n' => self.line += 1,
+                    b'\r' => {}
+                    byte => {
+                        row_line.get_or_insert(self.line);
+                        quoted.get_or_insert(byte == b'"');
+                    }
+                }
+                if byte == b'"' {
+                    quotes += 1;
+                }
+            }
+            field.extend_from_slice(&output[..written]);
+            self.input.consume(read);
+
+            match result {
+                ReadFieldResult::InputEmpty | ReadFieldResult::OutputFull => {}
+                ReadFieldResult::Field { record_end } => {
+                    let row_line = row_line.unwrap_or(self.line);
+                    let quoted = quoted.take() == Some(true);
+                    if quoted && quotes % 2 == 1 {
+                        return Err(CliError::UnterminatedQuote(row_line).into());
+                    }
+                    quotes = 0;
+
+                    row.push((String::from_utf8(std::mem::take(&mut field))?, quoted));
+                    if record_end {
+                        return Ok(Some((row_line, row)));
+                    }
+                }
+                ReadFieldResult::End => return Ok(None),
+            }
+        }
+    }
+}
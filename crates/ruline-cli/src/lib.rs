@@ -0,0 +1,33 @@
+use std::{
+    ffi::OsString,
+    io::{BufRead, Write},
+};
+
+use anyhow::Result;
+
+mod cli;
+mod command;
+mod error;
+mod input;
+mod spec;
+
+pub use error::CliError;
+pub use input::{read_records, Format, Records};
+
+/// Runs the command line with `args`, reading records from `stdin` when no
+/// input file is given. Returns `false` when a `test` case failed.
+pub fn run<I, T>(args: I, stdin: impl BufRead, stdout: &mut impl Write) -> Result<bool>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let matches = cli::command().try_get_matches_from(args)?;
+
+    match matches.subcommand() {
+        Some(("run", matches)) => command::run(matches, stdin, stdout).map(|_| true),
+        Some(("validate", matches)) => command::validate(matches, stdout).map(|_| true),
+        Some(("graph", matches)) => command::graph(matches, stdout).map(|_| true),
+        Some(("test", matches)) => command::test(matches, stdout),
+        _ => unreachable!("subcommand is required"),
+    }
+}
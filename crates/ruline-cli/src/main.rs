@@ -0,0 +1,25 @@
+use std::{
+    io::{self, BufWriter},
+    process::ExitCode,
+};
+
+fn main() -> ExitCode {
+    let stdin = io::stdin().lock();
+    let mut stdout = BufWriter::new(io::stdout().lock());
+
+    match ruline_cli::run(std::env::args_os(), stdin, &mut stdout) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => match err.downcast::<clap::Error>() {
+            Ok(err) => {
+                drop(stdout);
+                err.exit()
+            }
+            Err(err) => {
+                drop(stdout);
+                eprintln!("error: {}", err);
+                ExitCode::FAILURE
+            }
+        },
+    }
+}
@@ -0,0 +1,41 @@
+use std::fs;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use ruline_workflow::Workflow;
+use serde_json::Value;
+
+use crate::CliError;
+
+pub fn read_json(path: &str) -> Result<Value> {
+    let text = fs::read_to_string(path).map_err(|source| CliError::Read {
+        path: path.to_owned(),
+        source,
+    })?;
+
+    serde_json::from_str(&text).map_err(|source| {
+        CliError::Parse {
+            path: path.to_owned(),
+            source,
+        }
+        .into()
+    })
+}
+
+fn read_optional(matches: &ArgMatches, id: &str) -> Result<Value> {
+    match matches.get_one::<String>(id) {
+        Some(path) => read_json(path),
+        None => Ok(Value::Null),
+    }
+}
+
+/// Builds the workflow from the definition, output, variables and schema
+/// files given on the command line.
+pub fn load(matches: &ArgMatches) -> Result<Workflow> {
+    Workflow::builder()
+        .with_definition(read_optional(matches, "definition")?)
+        .with_output(read_optional(matches, "output")?)
+        .with_variables(read_optional(matches, "variables")?)
+        .with_schema(read_optional(matches, "schema")?)
+        .build()
+}
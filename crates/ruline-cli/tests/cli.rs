@@ -0,0 +1,197 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use std::{
+    io::{BufRead, BufReader},
+    process::Command,
+};
+
+use anyhow::Result;
+use insta::assert_snapshot;
+use ruline_cli::{read_records, Format};
+use serde_json::{json, Value};
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn run(args: &[&str], stdin: &str) -> (bool, String) {
+    let definition = fixture("definition.json");
+    let output = fixture("output.json");
+    let mut argv = vec!["ruline"];
+    argv.extend(&args[..1]);
+    argv.extend(["--definition", &definition, "--output", &output]);
+    argv.extend(&args[1..]);
+
+    let mut stdout = Vec::new();
+    let passed = ruline_cli::run(argv, stdin.as_bytes(), &mut stdout).unwrap();
+    (passed, String::from_utf8(stdout).unwrap())
+}
+
+#[test]
+fn test_run_jsonl_stdin() {
+    let (_, stdout) = run(
+        &["run"],
+        "{\"name\": \"John\", \"age\": 30}\n\n{\"name\": \"Jane\"}\n",
+    );
+
+    assert_eq!(
+        stdout,
+        concat!(
+            "{\"decision\":\"approved\",\"name\":\"John\"}\n",
+            "{\"error\":\"`/age` in data not found\"}\n",
+        )
+    );
+}
+
+#[test]
+fn test_run_csv_file() {
+    let input = fixture("input.csv");
+    let (_, stdout) = run(&["run", "--input", &input, "--workers", "2"], "");
+
+    assert_eq!(
+        stdout,
+        concat!(
+            "{\"decision\":\"approved\",\"name\":\"Doe, John\"}\n",
+            "{\"decision\":\"rejected\",\"name\":\"Jane\"}\n",
+            "{\"error\":\"Operands type invalid\"}\n",
+        )
+    );
+}
+
+fn read(input: impl BufRead, format: Format) -> Result<Vec<Value>> {
+    read_records(input, format).collect()
+}
+
+#[test]
+fn test_read_records() {
+    assert_eq!(
+        read("[{\"a\": 1}, {\"a\": 2}]".as_bytes(), Format::Json).unwrap(),
+        vec![json!({ "a": 1 }), json!({ "a": 2 })]
+    );
+    assert_eq!(
+        read("a,b,c\r\n007,\"x\ny\",true\r\n".as_bytes(), Format::Csv).unwrap(),
+        vec![json!({ "a": "007", "b": "x\ny", "c": true })]
+    );
+    let csv = "\u{feff}a,b,c\r\n42,\"42\",\"\"\r\n\"tr\"\"ue\",,1.5";
+    let records = vec![
+        json!({ "a": 42, "b": "42", "c": "" }),
+        json!({ "a": "tr\"ue", "b": null, "c": 1.5 }),
+    ];
+    assert_eq!(read(csv.as_bytes(), Format::Csv).unwrap(), records);
+    assert_eq!(
+        read(BufReader::with_capacity(2, csv.as_bytes()), Format::Csv).unwrap(),
+        records
+    );
+    assert_snapshot!(read("a,b\n1\n".as_bytes(), Format::Csv)
+        .unwrap_err()
+        .to_string());
+    assert_eq!(
+        read("a,b\n1,2\n3,\"4\n".as_bytes(), Format::Csv)
+            .unwrap_err()
+            .to_string(),
+        "Line 3: unterminated quoted field"
+    );
+}
+
+#[test]
+fn test_read_records_lazily() {
+    let mut records = read_records("{\"a\": 1}\n{\"a\":\n".as_bytes(), Format::Jsonl);
+    assert_eq!(records.next().unwrap().unwrap(), json!({ "a": 1 }));
+    assert!(records.next().unwrap().is_err());
+
+    let mut records = read_records("a\n1\n2,3\n".as_bytes(), Format::Csv);
+    assert_eq!(records.next().unwrap().unwrap(), json!({ "a": 1 }));
+    assert_eq!(
+        records.next().unwrap().unwrap_err().to_string(),
+        "Line 3: expected 1 columns, got 2"
+    );
+}
+
+#[test]
+fn test_run_chunks() {
+    let stdin = (0..150)
+        .map(|age| format!("{{\"name\": \"{}\", \"age\": {}}}\n", age, age))
+        .collect::<String>();
+    let (_, stdout) = run(&["run", "--workers", "1"], &stdin);
+
+    let names = stdout
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap()["name"].to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        (0..150)
+            .map(|age| json!(age.to_string()))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_run_invalid_record() {
+    let definition = fixture("definition.json");
+    let output = fixture("output.json");
+    let stdin = "{\"name\": \"John\", \"age\": 30}\n{\"name\":\n{\"name\": \"Jane\"}\n";
+
+    let mut stdout = Vec::new();
+    let err = ruline_cli::run(
+        [
+            "ruline",
+            "run",
+            "--definition",
+            &definition,
+            "--output",
+            &output,
+        ],
+        stdin.as_bytes(),
+        &mut stdout,
+    )
+    .unwrap_err();
+
+    assert!(err.to_string().starts_with("Line 2: "));
+    assert_eq!(
+        String::from_utf8(stdout).unwrap(),
+        "{\"decision\":\"approved\",\"name\":\"John\"}\n"
+    );
+}
+
+#[test]
+fn test_validate() {
+    assert_eq!(run(&["validate"], ""), (true, "ok\n".to_owned()));
+}
+
+#[test]
+fn test_graph() {
+    let (_, stdout) = run(&["graph"], "");
+    assert_snapshot!(stdout);
+
+    let (_, stdout) = run(&["graph", "--format", "mermaid"], "");
+    assert!(stdout.starts_with("flowchart TD\n"));
+}
+
+#[test]
+fn test_test() {
+    let cases = fixture("cases.jsonl");
+    let (passed, stdout) = run(&["test", "--cases", &cases], "");
+
+    assert!(!passed);
+    assert_snapshot!(stdout);
+}
+
+#[test]
+fn test_binary_exit_code() {
+    let status = Command::new(env!("CARGO_BIN_EXE_ruline"))
+        .args([
+            "validate",
+            "--definition",
+            "missing.json",
+            "--output",
+            "missing.json",
+        ])
+        .output()
+        .unwrap();
+
+    assert!(!status.status.success());
+    assert_snapshot!(String::from_utf8(status.stderr).unwrap());
+}
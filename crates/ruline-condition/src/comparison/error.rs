@@ -1,5 +1,8 @@
+use ruline_field::FieldType;
 use thiserror::Error;
 
+use super::ComparisonOperator;
+
 #[derive(Debug, Error)]
 pub enum ComparisonError {
     #[error("Expected {expected} operands, got {received}")]
@@ -11,4 +14,25 @@ pub enum ComparisonError {
     },
     #[error("Operands type invalid")]
     OperandTypeInvalid,
+    #[error("`{operator}` cannot compare operands of type {types}")]
+    OperandTypesIncompatible { operator: String, types: String },
+    #[error("Operator `{0}` not found")]
+    OperatorNotFound(String),
+    #[error("Operator name `{0}` is not an identifier")]
+    OperatorNameInvalid(String),
+    #[error("Operator name `{0}` is reserved for a built-in operator")]
+    OperatorNameReserved(String),
+}
+
+impl ComparisonError {
+    pub(crate) fn incompatible(operator: &ComparisonOperator, operands: &[FieldType]) -> Self {
+        ComparisonError::OperandTypesIncompatible {
+            operator: operator.to_string(),
+            types: operands
+                .iter()
+                .map(|operand| format!("`{}`", operand))
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
 }
@@ -1,5 +1,10 @@
+use std::fmt::{self, Display};
+
 use anyhow::Result;
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{self, EnumAccess, VariantAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
 use serde_json::Value;
 
 use crate::negate;
@@ -7,9 +12,14 @@ use crate::negate;
 mod error;
 mod negate;
 mod predicate;
+mod registry;
+mod signature;
 mod validate;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+pub use error::ComparisonError;
+pub use registry::{Arity, CustomOperator, OperatorRegistry};
+
+#[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum ComparisonOperator {
     Equals,
@@ -24,9 +34,103 @@ pub enum ComparisonOperator {
     NotExists,
     Empty,
     NotEmpty,
+    /// An operator looked up by name in an [`OperatorRegistry`]. Only the
+    /// names of the registry in [`OperatorRegistry::scope`] deserialize into
+    /// one.
+    #[serde(untagged)]
+    Custom(String),
+}
+
+impl Display for ComparisonOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ComparisonOperator::Equals => "equals",
+            ComparisonOperator::NotEquals => "not_equals",
+            ComparisonOperator::GreaterThan => "greater_than",
+            ComparisonOperator::GreaterThanOrEqual => "greater_than_or_equal",
+            ComparisonOperator::LessThan => "less_than",
+            ComparisonOperator::LessThanOrEqual => "less_than_or_equal",
+            ComparisonOperator::Contains => "contains",
+            ComparisonOperator::NotContains => "not_contains",
+            ComparisonOperator::Exists => "exists",
+            ComparisonOperator::NotExists => "not_exists",
+            ComparisonOperator::Empty => "empty",
+            ComparisonOperator::NotEmpty => "not_empty",
+            ComparisonOperator::Custom(name) => name,
+        };
+        f.write_str(name)
+    }
+}
+
+const BUILT_IN: &[&str] = &[
+    "equals",
+    "not_equals",
+    "greater_than",
+    "greater_than_or_equal",
+    "less_than",
+    "less_than_or_equal",
+    "contains",
+    "not_contains",
+    "exists",
+    "not_exists",
+    "empty",
+    "not_empty",
+];
+
+impl<'de> Deserialize<'de> for ComparisonOperator {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_enum("ComparisonOperator", BUILT_IN, OperatorVisitor)
+    }
+}
+
+struct OperatorVisitor;
+
+impl<'de> Visitor<'de> for OperatorVisitor {
+    type Value = ComparisonOperator;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("enum ComparisonOperator")
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<ComparisonOperator, A::Error> {
+        let (name, variant): (String, _) = data.variant()?;
+        variant.unit_variant()?;
+        ComparisonOperator::named(&name).ok_or_else(|| de::Error::unknown_variant(&name, BUILT_IN))
+    }
 }
 
 impl ComparisonOperator {
+    /// Returns the built-in operator named `name`, or else the custom one
+    /// registered under it in the registry in scope.
+    pub fn named(name: &str) -> Option<Self> {
+        match Self::built_in(name) {
+            Some(operator) => Some(operator),
+            None if registry::in_scope(name) => Some(ComparisonOperator::Custom(name.to_owned())),
+            None => None,
+        }
+    }
+
+    pub(crate) fn built_in(name: &str) -> Option<Self> {
+        let operator = match name {
+            "equals" => ComparisonOperator::Equals,
+            "not_equals" => ComparisonOperator::NotEquals,
+            "greater_than" => ComparisonOperator::GreaterThan,
+            "greater_than_or_equal" => ComparisonOperator::GreaterThanOrEqual,
+            "less_than" => ComparisonOperator::LessThan,
+            "less_than_or_equal" => ComparisonOperator::LessThanOrEqual,
+            "contains" => ComparisonOperator::Contains,
+            "not_contains" => ComparisonOperator::NotContains,
+            "exists" => ComparisonOperator::Exists,
+            "not_exists" => ComparisonOperator::NotExists,
+            "empty" => ComparisonOperator::Empty,
+            "not_empty" => ComparisonOperator::NotEmpty,
+            _ => return None,
+        };
+        Some(operator)
+    }
+
+    /// Evaluates a built-in operator. Custom operators are evaluated through
+    /// an [`OperatorRegistry`].
     pub fn eval(&self, operands: &[Value]) -> Result<bool> {
         match self {
             ComparisonOperator::Equals => predicate::equals(operands),
@@ -41,6 +145,9 @@ impl ComparisonOperator {
             ComparisonOperator::NotExists => negate!(predicate::exists, operands),
             ComparisonOperator::Empty => predicate::empty(operands),
             ComparisonOperator::NotEmpty => negate!(predicate::empty, operands),
+            ComparisonOperator::Custom(name) => {
+                Err(ComparisonError::OperatorNotFound(name.to_owned()).into())
+            }
         }
     }
 }
@@ -0,0 +1,205 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Debug, sync::Arc};
+
+use anyhow::Result;
+use ruline_field::FieldType;
+use serde_json::Value;
+
+use super::{error::ComparisonError, ComparisonOperator};
+
+/// Number of operands an operator takes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arity {
+    Exact(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    fn check(&self, received: usize) -> Result<(), ComparisonError> {
+        match *self {
+            Arity::Exact(expected) if received != expected => {
+                Err(ComparisonError::OperandsAmountMismatch { expected, received })
+            }
+            Arity::AtLeast(min_required) if received < min_required => {
+                Err(ComparisonError::OperandsAmountLessThanRequired {
+                    min_required,
+                    received,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A comparison provided by the host application, usable in comparisons by
+/// the name it is registered under.
+pub trait CustomOperator: Debug + Send + Sync {
+    fn arity(&self) -> Arity;
+
+    /// Whether operands of the given types, known before execution, can be
+    /// compared. Accepts any by default.
+    fn check(&self, _operands: &[FieldType]) -> bool {
+        true
+    }
+
+    fn eval(&self, operands: &[Value]) -> Result<bool>;
+}
+
+#[derive(Debug, Clone)]
+struct Registered {
+    operator: Arc<dyn CustomOperator>,
+    negated: bool,
+}
+
+/// Custom comparison operators by name, each optionally registered along
+/// with a negated form evaluating to the opposite result.
+#[derive(Debug, Clone, Default)]
+pub struct OperatorRegistry {
+    operators: HashMap<String, Registered>,
+}
+
+impl OperatorRegistry {
+    /// Registers `operator` under `name`, replacing any operator registered
+    /// under it before. Names must be identifiers other than the ones of the
+    /// built-in operators.
+    pub fn register(
+        self,
+        name: &str,
+        operator: impl CustomOperator + 'static,
+    ) -> Result<Self, ComparisonError> {
+        self.insert(name, Arc::new(operator), false)
+    }
+
+    /// Registers `operator` under `name` and its negation under `negation`.
+    pub fn register_negated(
+        self,
+        name: &str,
+        negation: &str,
+        operator: impl CustomOperator + 'static,
+    ) -> Result<Self, ComparisonError> {
+        let operator: Arc<dyn CustomOperator> = Arc::new(operator);
+        self.insert(name, Arc::clone(&operator), false)?
+            .insert(negation, operator, true)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.operators.contains_key(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.operators.keys().map(String::as_str)
+    }
+
+    /// Runs `f` with the operators of the registry known by name, so the
+    /// comparisons and table tests it deserializes, and the expressions it
+    /// parses, can use them. Outside of a scope only the built-in operators
+    /// are known.
+    pub fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        enter(Scope::Registry(self.to_owned()), f)
+    }
+
+    /// Runs `f` with every identifier known as an operator, for reading
+    /// definitions apart from the registry they are built with.
+    pub fn any_name<T>(f: impl FnOnce() -> T) -> T {
+        enter(Scope::Any, f)
+    }
+
+    /// Evaluates `operator`, looking it up in the registry when custom.
+    pub fn eval(&self, operator: &ComparisonOperator, operands: &[Value]) -> Result<bool> {
+        match operator {
+            ComparisonOperator::Custom(name) => {
+                let registered = self.get(name)?;
+                registered.operator.arity().check(operands.len())?;
+                let result = registered.operator.eval(operands)?;
+                Ok(result != registered.negated)
+            }
+            operator => operator.eval(operands),
+        }
+    }
+
+    /// Checks the operand types of `operator`, looking it up in the registry
+    /// when custom.
+    pub fn check(&self, operator: &ComparisonOperator, operands: &[FieldType]) -> Result<()> {
+        match operator {
+            ComparisonOperator::Custom(name) => {
+                let registered = self.get(name)?;
+                registered.operator.arity().check(operands.len())?;
+                match registered.operator.check(operands) {
+                    true => Ok(()),
+                    false => Err(ComparisonError::incompatible(operator, operands).into()),
+                }
+            }
+            operator => operator.check(operands),
+        }
+    }
+
+    /// Checks that `operator` is registered when custom and takes
+    /// `received` operands.
+    pub fn validate(
+        &self,
+        operator: &ComparisonOperator,
+        received: usize,
+    ) -> Result<(), ComparisonError> {
+        match operator {
+            ComparisonOperator::Custom(name) => self.get(name)?.operator.arity().check(received),
+            _ => Ok(()),
+        }
+    }
+
+    fn get(&self, name: &str) -> Result<&Registered, ComparisonError> {
+        self.operators
+            .get(name)
+            .ok_or_else(|| ComparisonError::OperatorNotFound(name.to_owned()))
+    }
+
+    fn insert(
+        mut self,
+        name: &str,
+        operator: Arc<dyn CustomOperator>,
+        negated: bool,
+    ) -> Result<Self, ComparisonError> {
+        let mut chars = name.chars();
+        let identifier = matches!(chars.next(), Some(char) if char.is_ascii_alphabetic() || char == '_')
+            && chars.all(|char| char.is_ascii_alphanumeric() || char == '_');
+        if !identifier {
+            return Err(ComparisonError::OperatorNameInvalid(name.to_owned()));
+        }
+        if ComparisonOperator::built_in(name).is_some() {
+            return Err(ComparisonError::OperatorNameReserved(name.to_owned()));
+        }
+
+        self.operators
+            .insert(name.to_owned(), Registered { operator, negated });
+        Ok(self)
+    }
+}
+
+enum Scope {
+    Registry(OperatorRegistry),
+    Any,
+}
+
+thread_local! {
+    static SCOPE: RefCell<Option<Scope>> = const { RefCell::new(None) };
+}
+
+fn enter<T>(scope: Scope, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Scope>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPE.with(|scope| *scope.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(SCOPE.with(|current| current.replace(Some(scope))));
+    f()
+}
+
+/// Whether `name` is a custom operator in the scope.
+pub(crate) fn in_scope(name: &str) -> bool {
+    SCOPE.with(|scope| match &*scope.borrow() {
+        Some(Scope::Registry(operators)) => operators.contains(name),
+        Some(Scope::Any) => true,
+        None => false,
+    })
+}
@@ -0,0 +1,66 @@
+use anyhow::Result;
+use ruline_field::FieldType;
+
+use super::{error::ComparisonError, ComparisonOperator};
+use crate::{validate_min_operands, validate_operands};
+
+fn ordered(operands: &[FieldType]) -> bool {
+    match (operands[0], operands[1]) {
+        (FieldType::Any, _) | (_, FieldType::Any) => true,
+        (left, right) => {
+            left == right
+                && matches!(
+                    left,
+                    FieldType::Number | FieldType::String | FieldType::Array
+                )
+        }
+    }
+}
+
+fn emptiable(operand: FieldType) -> bool {
+    matches!(
+        operand,
+        FieldType::Any | FieldType::Null | FieldType::Array | FieldType::Object | FieldType::String
+    )
+}
+
+impl ComparisonOperator {
+    pub fn check(&self, operands: &[FieldType]) -> Result<()> {
+        let valid = match self {
+            ComparisonOperator::Equals
+            | ComparisonOperator::NotEquals
+            | ComparisonOperator::Exists
+            | ComparisonOperator::NotExists => {
+                let min = match self {
+                    ComparisonOperator::Equals | ComparisonOperator::NotEquals => 2,
+                    _ => 1,
+                };
+                validate_min_operands!(operands, min);
+                true
+            }
+            ComparisonOperator::GreaterThan
+            | ComparisonOperator::GreaterThanOrEqual
+            | ComparisonOperator::LessThan
+            | ComparisonOperator::LessThanOrEqual => {
+                validate_operands!(operands, 2);
+                ordered(operands)
+            }
+            ComparisonOperator::Contains | ComparisonOperator::NotContains => {
+                validate_operands!(operands, 2);
+                operands[1].is(FieldType::Array)
+            }
+            ComparisonOperator::Empty | ComparisonOperator::NotEmpty => {
+                validate_operands!(operands, 1);
+                emptiable(operands[0])
+            }
+            ComparisonOperator::Custom(name) => {
+                return Err(ComparisonError::OperatorNotFound(name.to_owned()).into())
+            }
+        };
+
+        match valid {
+            true => Ok(()),
+            false => Err(ComparisonError::incompatible(self, operands).into()),
+        }
+    }
+}
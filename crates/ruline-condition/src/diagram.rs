@@ -0,0 +1,119 @@
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Box,
+    Rounded,
+    Diamond,
+    Circle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeStyle {
+    Solid,
+    Dashed,
+}
+
+/// A graph of labelled nodes and edges that renders to Graphviz DOT or
+/// Mermaid. Nodes are referenced by their position.
+#[derive(Debug, Default)]
+pub struct Diagram {
+    nodes: Vec<(String, Shape)>,
+    edges: Vec<(usize, usize, Option<String>, EdgeStyle)>,
+}
+
+impl Diagram {
+    pub fn node(&mut self, label: impl Into<String>, shape: Shape) -> usize {
+        self.nodes.push((label.into(), shape));
+        self.nodes.len() - 1
+    }
+
+    pub fn edge(&mut self, from: usize, to: usize, label: Option<String>, style: EdgeStyle) {
+        self.edges.push((from, to, label, style));
+    }
+
+    pub fn to_dot(&self, name: &str) -> String {
+        let mut dot = String::new();
+        let escape = |label: &str| {
+            label
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n")
+        };
+
+        writeln!(dot, "digraph \"{}\" {{", escape(name)).unwrap();
+        for (index, (label, shape)) in self.nodes.iter().enumerate() {
+            let shape = match shape {
+                Shape::Box => "box",
+                Shape::Rounded => "box, style=rounded",
+                Shape::Diamond => "diamond",
+                Shape::Circle => "circle",
+            };
+            writeln!(
+                dot,
+                "  n{} [label=\"{}\", shape={}];",
+                index,
+                escape(label),
+                shape
+            )
+            .unwrap();
+        }
+        for (from, to, label, style) in &self.edges {
+            let mut attributes = Vec::new();
+            if let Some(label) = label {
+                attributes.push(format!("label=\"{}\"", escape(label)));
+            }
+            if *style == EdgeStyle::Dashed {
+                attributes.push("style=dashed".to_owned());
+            }
+            match attributes.is_empty() {
+                true => writeln!(dot, "  n{} -> n{};", from, to),
+                false => writeln!(dot, "  n{} -> n{} [{}];", from, to, attributes.join(", ")),
+            }
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart TD\n");
+        let escape = |label: &str| {
+            label
+                .replace('"', "#quot;")
+                .replace('<', "#lt;")
+                .replace('>', "#gt;")
+                .replace('\n', "<br/>")
+        };
+
+        for (index, (label, shape)) in self.nodes.iter().enumerate() {
+            let label = escape(label);
+            let node = match shape {
+                Shape::Box => format!("[\"{}\"]", label),
+                Shape::Rounded => format!("(\"{}\")", label),
+                Shape::Diamond => format!("{{\"{}\"}}", label),
+                Shape::Circle => format!("((\"{}\"))", label),
+            };
+            writeln!(mermaid, "  n{}{}", index, node).unwrap();
+        }
+        for (from, to, label, style) in &self.edges {
+            let arrow = match style {
+                EdgeStyle::Solid => "-->",
+                EdgeStyle::Dashed => "-.->",
+            };
+            match label {
+                Some(label) => writeln!(
+                    mermaid,
+                    "  n{} {}|\"{}\"| n{}",
+                    from,
+                    arrow,
+                    escape(label),
+                    to
+                ),
+                None => writeln!(mermaid, "  n{} {} n{}", from, arrow, to),
+            }
+            .unwrap();
+        }
+        mermaid
+    }
+}
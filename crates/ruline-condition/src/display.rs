@@ -0,0 +1,69 @@
+use std::fmt::{self, Display};
+
+use crate::{ComparisonOperator, Expression, LogicalOperator};
+
+impl ComparisonOperator {
+    /// Infix symbol of binary operators in the textual expression syntax.
+    pub fn symbol(&self) -> Option<&'static str> {
+        match self {
+            ComparisonOperator::Equals => Some("=="),
+            ComparisonOperator::NotEquals => Some("!="),
+            ComparisonOperator::GreaterThan => Some(">"),
+            ComparisonOperator::GreaterThanOrEqual => Some(">="),
+            ComparisonOperator::LessThan => Some("<"),
+            ComparisonOperator::LessThanOrEqual => Some("<="),
+            ComparisonOperator::Contains => Some("in"),
+            ComparisonOperator::NotContains => Some("not in"),
+            _ => None,
+        }
+    }
+}
+
+impl Display for LogicalOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogicalOperator::And => f.write_str("and"),
+            LogicalOperator::Or => f.write_str("or"),
+        }
+    }
+}
+
+/// Renders expressions in the textual expression syntax, e.g.
+/// `data.age >= 18 and (var.country in ["AR", "UY"] or exists(data.vip))`.
+impl Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Comparison {
+                operator, operands, ..
+            } => match (operator.symbol(), operands.as_slice()) {
+                (Some(symbol), [left, right]) => write!(f, "{} {} {}", left, symbol, right),
+                _ => {
+                    write!(f, "{}(", operator)?;
+                    for (index, operand) in operands.iter().enumerate() {
+                        if index > 0 {
+                            f.write_str(", ")?;
+                        }
+                        write!(f, "{}", operand)?;
+                    }
+                    f.write_str(")")
+                }
+            },
+            Expression::Logical {
+                operator,
+                expressions,
+                ..
+            } => {
+                for (index, expression) in expressions.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " {} ", operator)?;
+                    }
+                    match expression {
+                        Expression::Logical { .. } => write!(f, "({})", expression)?,
+                        Expression::Comparison { .. } => write!(f, "{}", expression)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::ComparisonError;
+
 #[derive(Debug, Error)]
 pub enum ConditionError {
     #[error("Cycle detected")]
@@ -12,6 +14,8 @@ pub enum ConditionError {
     LogicalChildrenCountInvalid { id: String, childrens_count: usize },
     #[error("Comparison with id `{0}` must not have any children")]
     ComparisonChildrenInvalid(String),
+    #[error("Comparison with id `{id}` is invalid: {source}")]
+    ComparisonOperatorInvalid { id: String, source: ComparisonError },
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
 }
@@ -6,37 +6,71 @@ use petgraph::{
     graph::{DiGraph, NodeIndex},
     visit::Dfs,
 };
-use ruline_context::Context;
-use ruline_field::Field;
+use serde_json::Value;
 
-use crate::{error::ConditionError, Expression, LogicalOperator};
+use crate::{
+    error::ConditionError, ComparisonTrace, Expression, LogicalOperator, OperatorRegistry,
+};
 
-pub struct Evaluator<'a> {
+pub struct Evaluator<'a, F> {
     graph: &'a DiGraph<Expression, ()>,
+    operands: F,
+    operators: &'a OperatorRegistry,
     dfs: Dfs<NodeIndex, FixedBitSet>,
     stack: Vec<(NodeIndex, Vec<bool>)>,
+    trace: Option<Vec<ComparisonTrace>>,
 }
 
-impl<'a> Evaluator<'a> {
-    pub fn new(graph: &'a DiGraph<Expression, ()>, root: NodeIndex) -> Self {
+/// Evaluates the expressions under a root, resolving the operands of each
+/// comparison, by node, through `operands`.
+impl<'a, F> Evaluator<'a, F>
+where
+    F: Fn(NodeIndex) -> Result<Vec<Value>>,
+{
+    pub fn new(
+        graph: &'a DiGraph<Expression, ()>,
+        operands: F,
+        operators: &'a OperatorRegistry,
+        root: NodeIndex,
+    ) -> Self {
         let dfs = Dfs::new(graph, root);
         let stack = Vec::new();
-        Self { graph, dfs, stack }
+        Self {
+            graph,
+            operands,
+            operators,
+            dfs,
+            stack,
+            trace: None,
+        }
+    }
+
+    pub fn traced(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
     }
 
-    pub fn eval(&mut self, ctx: &'a Context) -> Result<bool> {
+    pub fn into_trace(self) -> Vec<ComparisonTrace> {
+        self.trace.unwrap_or_default()
+    }
+
+    pub fn eval(&mut self) -> Result<bool> {
         while let Some(node) = self.dfs.next(&self.graph) {
             match &self.graph[node] {
-                Expression::Comparison {
-                    operator, operands, ..
-                } => {
-                    let operands = operands
-                        .iter()
-                        .map(Field::from)
-                        .map(|field| field.process(ctx))
-                        .collect::<Result<Vec<_>>>()?;
+                Expression::Comparison { id, operator, .. } => {
+                    let operands = (self.operands)(node)?;
+
+                    let result = self.operators.eval(operator, &operands)?;
+                    if let Some(trace) = self.trace.as_mut() {
+                        trace.push(ComparisonTrace {
+                            id: id.to_owned(),
+                            operator: operator.to_owned(),
+                            operands,
+                            result,
+                        });
+                    }
 
-                    if let Some(res) = self.handle_result(operator.eval(&operands)?)? {
+                    if let Some(res) = self.handle_result(result)? {
                         return Ok(res);
                     }
                 }
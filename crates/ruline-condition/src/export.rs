@@ -0,0 +1,45 @@
+use crate::{
+    diagram::{Diagram, EdgeStyle, Shape},
+    Condition, Expression,
+};
+
+impl Condition {
+    /// Diagram of the expression trees, with logical operators as circles
+    /// and comparisons labelled with their expression id and text.
+    pub fn diagram(&self) -> Diagram {
+        let mut diagram = Diagram::default();
+        let nodes = self
+            .graph
+            .node_indices()
+            .map(|node| match &self.graph[node] {
+                Expression::Logical { id, operator, .. } => {
+                    diagram.node(format!("{}\n{}", id, operator), Shape::Circle)
+                }
+                expression @ Expression::Comparison { id, .. } => {
+                    diagram.node(format!("{}\n{}", id, expression), Shape::Box)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for edge in self.graph.edge_indices() {
+            if let Some((parent, child)) = self.graph.edge_endpoints(edge) {
+                diagram.edge(
+                    nodes[parent.index()],
+                    nodes[child.index()],
+                    None,
+                    EdgeStyle::Solid,
+                );
+            }
+        }
+
+        diagram
+    }
+
+    pub fn to_dot(&self) -> String {
+        self.diagram().to_dot("condition")
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        self.diagram().to_mermaid()
+    }
+}
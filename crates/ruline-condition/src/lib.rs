@@ -1,22 +1,32 @@
 use std::collections::HashMap;
 
 use anyhow::Result;
-use comparison::ComparisonOperator;
+pub use comparison::{
+    Arity, ComparisonError, ComparisonOperator, CustomOperator, OperatorRegistry,
+};
+pub use diagram::{Diagram, EdgeStyle, Shape};
 pub use error::ConditionError;
 use evaluate::Evaluator;
+pub use parse::{parse_field, ParseError};
 use petgraph::{
     graph::{DiGraph, NodeIndex},
     visit::Dfs,
 };
 use ruline_context::Context;
-use ruline_field::{Field, FieldDefinition};
+use ruline_field::{Field, FieldDefinition, FunctionRegistry, TypeEnv};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
+pub use trace::{Branch, ComparisonTrace, ConditionTrace};
 
 mod comparison;
+mod diagram;
+mod display;
 mod error;
 mod evaluate;
+mod export;
+mod parse;
 mod test;
+mod trace;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -40,6 +50,32 @@ pub enum ConditionDefinition {
     },
 }
 
+impl ConditionDefinition {
+    /// Sorts and deduplicates the dependant lists, which are sets: the order
+    /// they are listed in has no effect on processing.
+    pub fn normalize(&mut self) {
+        fn normalize(ids: &mut Vec<String>) {
+            ids.sort();
+            ids.dedup();
+        }
+
+        match self {
+            ConditionDefinition::Binary {
+                fallbacks, results, ..
+            } => {
+                normalize(fallbacks);
+                normalize(results);
+            }
+            ConditionDefinition::Decision {
+                fallbacks, results, ..
+            } => {
+                normalize(fallbacks);
+                results.values_mut().for_each(normalize);
+            }
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum Expression {
@@ -56,19 +92,24 @@ pub enum Expression {
 }
 
 impl Expression {
-    fn get_id(&self) -> String {
+    pub fn get_id(&self) -> String {
         match self {
             Expression::Comparison { id, .. } | Expression::Logical { id, .. } => id.to_owned(),
         }
     }
 
-    fn setup_graph(&self, graph: &mut DiGraph<Expression, ()>, parent: Option<NodeIndex>) {
+    fn setup_graph(
+        &self,
+        graph: &mut DiGraph<Expression, ()>,
+        parent: Option<NodeIndex>,
+    ) -> NodeIndex {
         match self {
             Expression::Comparison { .. } => {
                 let idx = graph.add_node(self.to_owned());
                 if let Some(parent_id) = parent {
                     graph.add_edge(parent_id, idx, ());
                 }
+                idx
             }
             Expression::Logical { expressions, .. } => {
                 let idx = graph.add_node(self.to_owned());
@@ -78,87 +119,173 @@ impl Expression {
                 for expressions in expressions {
                     expressions.setup_graph(graph, Some(idx));
                 }
+                idx
             }
         }
     }
 }
 
+/// Evaluates expressions and selects the dependants to run next. Once given
+/// an id with `with_id`, every evaluation publishes under it an output with
+/// the `branch` taken (`results` or `fallbacks`) and the ids of the `matched`
+/// top-level expressions, e.g. `{ "branch": "results", "matched": ["100"] }`.
 #[derive(Debug)]
 pub struct Condition {
+    id: Option<String>,
     definition: ConditionDefinition,
     graph: DiGraph<Expression, ()>,
+    operands: Vec<Vec<Field>>,
+    operators: OperatorRegistry,
+    roots: Vec<NodeIndex>,
     dependencies: Vec<String>,
     dependants: Vec<String>,
 }
 
 impl Condition {
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_owned());
+        self
+    }
+
+    /// Rebuilds the operands resolving the custom functions they call in
+    /// `functions`.
+    pub fn with_functions(mut self, functions: &FunctionRegistry) -> Self {
+        self.operands = Self::operands(&self.graph, functions);
+        self
+    }
+
+    /// Evaluates and validates the custom operators of the comparisons
+    /// through `operators`.
+    pub fn with_operators(mut self, operators: &OperatorRegistry) -> Self {
+        self.operators = operators.to_owned();
+        self
+    }
+
     pub fn evaluate(&self, ctx: &Context) -> Result<Vec<String>> {
-        match &self.definition {
+        self.run(ctx, false).map(|(next_calls, _)| next_calls)
+    }
+
+    pub fn evaluate_with_trace(&self, ctx: &Context) -> Result<(Vec<String>, ConditionTrace)> {
+        let (next_calls, trace) = self.run(ctx, true)?;
+        Ok((next_calls, trace.unwrap()))
+    }
+
+    /// Evaluates the condition awaiting the async functions its operands
+    /// call. Operands are then all processed before any comparison.
+    pub async fn evaluate_async(&self, ctx: &Context) -> Result<Vec<String>> {
+        if !self.operands.iter().flatten().any(Field::is_async) {
+            return self.evaluate(ctx);
+        }
+
+        let mut values = Vec::with_capacity(self.operands.len());
+        for operands in &self.operands {
+            let mut processed = Vec::with_capacity(operands.len());
+            for operand in operands {
+                processed.push(operand.process_async(ctx).await?);
+            }
+            values.push(processed);
+        }
+
+        let (next_calls, _) = self.select(ctx, false, |node: NodeIndex| {
+            Ok(values[node.index()].to_owned())
+        })?;
+        Ok(next_calls)
+    }
+
+    fn run(&self, ctx: &Context, traced: bool) -> Result<(Vec<String>, Option<ConditionTrace>)> {
+        self.select(ctx, traced, |node: NodeIndex| {
+            self.operands[node.index()]
+                .iter()
+                .map(|field| field.process(ctx))
+                .collect()
+        })
+    }
+
+    fn select(
+        &self,
+        ctx: &Context,
+        traced: bool,
+        operands: impl Fn(NodeIndex) -> Result<Vec<Value>>,
+    ) -> Result<(Vec<String>, Option<ConditionTrace>)> {
+        let mut matched = Vec::new();
+        let mut comparisons = Vec::new();
+
+        for root in &self.roots {
+            let mut evaluator = Evaluator::new(&self.graph, &operands, &self.operators, *root);
+            if traced {
+                evaluator = evaluator.traced();
+            }
+
+            if evaluator.eval()? {
+                matched.push(*root);
+            }
+            comparisons.extend(evaluator.into_trace());
+        }
+
+        let (branch, next_calls) = match &self.definition {
             ConditionDefinition::Binary {
                 fallbacks, results, ..
-            } => {
-                let root = self.graph.node_indices().next().unwrap();
-                let passed = Evaluator::new(&self.graph, root).eval(ctx)?;
-                match passed {
-                    true => Ok(results.to_vec()),
-                    false => Ok(fallbacks.to_vec()),
-                }
-            }
+            } => match matched.is_empty() {
+                false => (Branch::Results, results.to_vec()),
+                true => (Branch::Fallbacks, fallbacks.to_vec()),
+            },
             ConditionDefinition::Decision {
-                expressions,
-                fallbacks,
-                results,
-                ..
+                fallbacks, results, ..
             } => {
-                let mut indices = self.graph.node_indices().collect::<Vec<_>>();
-                let mut indices = indices.iter_mut();
                 let mut next_calls = Vec::new();
-
-                for _ in expressions {
-                    let root = indices.next().unwrap();
-                    if Evaluator::new(&self.graph, *root).eval(ctx)? {
-                        next_calls.extend(
-                            results
-                                .get(&self.graph[*root].get_id().to_string())
-                                .ok_or(ConditionError::ExpressionInvalid)?
-                                .to_vec(),
-                        );
-                    }
+                for root in &matched {
+                    next_calls.extend(
+                        results
+                            .get(&self.graph[*root].get_id())
+                            .ok_or(ConditionError::ExpressionInvalid)?
+                            .to_vec(),
+                    );
                 }
 
-                if next_calls.is_empty() {
-                    next_calls.extend(fallbacks.to_owned());
+                match next_calls.is_empty() {
+                    false => (Branch::Results, next_calls),
+                    true => (Branch::Fallbacks, fallbacks.to_owned()),
                 }
-
-                Ok(next_calls)
             }
+        };
+
+        let matched = matched
+            .iter()
+            .map(|root| self.graph[*root].get_id())
+            .collect::<Vec<_>>();
+        if let Some(id) = &self.id {
+            ctx.set_output(
+                id.to_owned(),
+                json!({ "branch": branch, "matched": matched }),
+            );
         }
+
+        let trace = traced.then_some(ConditionTrace {
+            branch,
+            matched,
+            comparisons,
+        });
+
+        Ok((next_calls, trace))
     }
 
     pub fn validate(&self) -> Result<()> {
         match &self.definition {
             ConditionDefinition::Binary { .. } => {
                 let root = self
-                    .graph
-                    .node_indices()
-                    .next()
+                    .roots
+                    .first()
                     .ok_or(ConditionError::ExpressionInvalid)?;
 
-                self.validate_conditions(root)
+                self.validate_conditions(*root)
             }
             ConditionDefinition::Decision { expressions, .. } => {
                 if expressions.is_empty() {
                     return Err(ConditionError::ExpressionInvalid.into());
                 }
 
-                for _ in expressions {
-                    let root = self
-                        .graph
-                        .node_indices()
-                        .next()
-                        .ok_or(ConditionError::ExpressionInvalid)?;
-
-                    self.validate_conditions(root)?;
+                for root in &self.roots {
+                    self.validate_conditions(*root)?;
                 }
 
                 Ok(())
@@ -166,6 +293,50 @@ impl Condition {
         }
     }
 
+    /// Infers the operand types of every comparison and returns the
+    /// mismatches found, keyed by expression id.
+    pub fn check_types(&self, env: &dyn TypeEnv) -> Vec<(String, anyhow::Error)> {
+        let mut mismatches = Vec::new();
+
+        for node in self.graph.node_indices() {
+            if let Expression::Comparison {
+                id,
+                operator,
+                operands,
+            } = &self.graph[node]
+            {
+                let checked = operands
+                    .iter()
+                    .map(|operand| operand.infer_type(env))
+                    .collect::<Result<Vec<_>>>()
+                    .and_then(|types| self.operators.check(operator, &types));
+
+                if let Err(err) = checked {
+                    mismatches.push((id.to_owned(), err));
+                }
+            }
+        }
+
+        mismatches
+    }
+
+    fn operands(graph: &DiGraph<Expression, ()>, functions: &FunctionRegistry) -> Vec<Vec<Field>> {
+        graph
+            .node_indices()
+            .map(|node| match &graph[node] {
+                Expression::Comparison { operands, .. } => operands
+                    .iter()
+                    .map(|operand| Field::with_functions(operand, functions))
+                    .collect(),
+                _ => vec![],
+            })
+            .collect()
+    }
+
+    pub fn definition(&self) -> &ConditionDefinition {
+        &self.definition
+    }
+
     pub fn dependencies(&self) -> Vec<String> {
         self.dependencies.to_owned()
     }
@@ -187,6 +358,18 @@ impl Condition {
                     }
                     .into());
                 }
+                Expression::Comparison {
+                    id,
+                    operator,
+                    operands,
+                } => {
+                    self.operators
+                        .validate(operator, operands.len())
+                        .map_err(|source| ConditionError::ComparisonOperatorInvalid {
+                            id: id.to_owned(),
+                            source,
+                        })?;
+                }
                 _ => {}
             }
         }
@@ -212,28 +395,23 @@ impl TryFrom<ConditionDefinition> for Condition {
     fn try_from(definition: ConditionDefinition) -> Result<Self, Self::Error> {
         let mut graph = DiGraph::new();
 
-        match &definition {
+        let roots = match &definition {
             ConditionDefinition::Binary {
                 expression: expressions,
                 ..
-            } => {
-                expressions.setup_graph(&mut graph, None);
-            }
-            ConditionDefinition::Decision { expressions, .. } => {
-                for expressions in expressions {
-                    expressions.setup_graph(&mut graph, None);
-                }
-            }
-        }
+            } => vec![expressions.setup_graph(&mut graph, None)],
+            ConditionDefinition::Decision { expressions, .. } => expressions
+                .iter()
+                .map(|expressions| expressions.setup_graph(&mut graph, None))
+                .collect(),
+        };
 
-        let mut dependencies = graph
-            .node_indices()
-            .filter_map(|node| match &graph[node] {
-                Expression::Comparison { operands, .. } => Some(operands),
-                _ => None,
-            })
+        let operands = Self::operands(&graph, &FunctionRegistry::default());
+
+        let mut dependencies = operands
+            .iter()
             .flatten()
-            .flat_map(|operand| Field::from(operand).dependencies())
+            .flat_map(|operand| operand.dependencies())
             .collect::<Vec<_>>();
 
         dependencies.sort();
@@ -260,10 +438,14 @@ impl TryFrom<ConditionDefinition> for Condition {
         dependants.dedup();
 
         Ok(Self {
+            id: None,
             definition,
             dependencies,
             dependants,
             graph,
+            operands,
+            operators: OperatorRegistry::default(),
+            roots,
         })
     }
 }
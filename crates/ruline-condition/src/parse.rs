@@ -0,0 +1,457 @@
+use std::ops::Range;
+
+use ruline_field::{FieldDefinition, Function};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{ComparisonOperator, Expression, LogicalOperator};
+
+/// Error in the textual expression syntax, with the byte range of the
+/// offending input.
+#[derive(Debug, Error, Clone, PartialEq)]
+#[error("{message} at {}..{}", span.start, span.end)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Range<usize>,
+}
+
+type Result<T> = std::result::Result<T, ParseError>;
+
+impl Expression {
+    /// Parses the textual expression syntax, e.g.
+    /// `data.age >= 18 and (var.country in ["AR", "UY"] or exists(data.vip))`.
+    /// The root expression gets `id` and the nested ones `{id}.1`, `{id}.2`
+    /// and so on, following their position.
+    pub fn parse(id: &str, source: &str) -> Result<Expression> {
+        let mut parser = Parser::new(source);
+        let mut expression = parser.or()?;
+        parser.end()?;
+
+        expression.assign_ids(id);
+        Ok(expression)
+    }
+
+    fn assign_ids(&mut self, id: &str) {
+        match self {
+            Expression::Comparison { id: own, .. } => *own = id.to_owned(),
+            Expression::Logical {
+                id: own,
+                expressions,
+                ..
+            } => {
+                *own = id.to_owned();
+                for (index, expression) in expressions.iter_mut().enumerate() {
+                    expression.assign_ids(&format!("{}.{}", id, index + 1));
+                }
+            }
+        }
+    }
+}
+
+/// Parses a single field in the textual expression syntax, e.g. `data.age`,
+/// `output["1"].fee` or `add(data.a, 2)`.
+pub fn parse_field(source: &str) -> Result<FieldDefinition> {
+    let mut parser = Parser::new(source);
+    let field = parser.operand()?;
+    parser.end()?;
+    Ok(field)
+}
+
+fn is_identifier_start(char: char) -> bool {
+    char.is_ascii_alphabetic() || char == '_'
+}
+
+fn is_identifier(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '_'
+}
+
+/// Deepest nesting of parentheses and calls parsed, so hostile sources
+/// cannot exhaust the stack.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    fn rest(&self) -> &str {
+        &self.source[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.rest().chars().next()
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        match self.rest().starts_with(token) {
+            true => {
+                self.pos += token.len();
+                true
+            }
+            false => false,
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        match self.eat(token) {
+            true => Ok(()),
+            false => Err(self.unexpected(&format!("`{}`", token))),
+        }
+    }
+
+    /// Span of the identifier at the cursor, without consuming it.
+    fn peek_identifier(&mut self) -> Option<Range<usize>> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        if !rest.starts_with(is_identifier_start) {
+            return None;
+        }
+        let len = rest.find(|char| !is_identifier(char)).unwrap_or(rest.len());
+        Some(self.pos..self.pos + len)
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek_identifier() {
+            Some(span) if &self.source[span.to_owned()] == keyword => {
+                self.pos = span.end;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Error for the token at the cursor, which is not what was `expected`.
+    fn unexpected(&mut self, expected: &str) -> ParseError {
+        self.skip_whitespace();
+        let span = match self.peek_identifier() {
+            Some(span) => span,
+            None => {
+                let rest = self.rest();
+                let len = match rest.chars().next() {
+                    Some('0'..='9') => rest
+                        .find(|char: char| !char.is_ascii_alphanumeric() && char != '.')
+                        .unwrap_or(rest.len()),
+                    char => char.map_or(0, char::len_utf8),
+                };
+                self.pos..self.pos + len
+            }
+        };
+
+        let found = match span.is_empty() {
+            true => "end of input".to_owned(),
+            false => format!("`{}`", &self.source[span.to_owned()]),
+        };
+        ParseError {
+            message: format!("Expected {}, found {}", expected, found),
+            span,
+        }
+    }
+
+    /// Runs `parse` one level deeper, the level being opened at `start`.
+    fn nested<T>(&mut self, start: usize, parse: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        if self.depth == MAX_DEPTH {
+            return Err(ParseError {
+                message: format!("Nesting deeper than {} levels", MAX_DEPTH),
+                span: start..start + 1,
+            });
+        }
+
+        self.depth += 1;
+        let parsed = parse(self);
+        self.depth -= 1;
+        parsed
+    }
+
+    fn end(&mut self) -> Result<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.unexpected("`and`, `or` or end of input")),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expression> {
+        let mut expressions = vec![self.and()?];
+        while self.keyword("or") {
+            expressions.push(self.and()?);
+        }
+        Ok(Self::logical(LogicalOperator::Or, expressions))
+    }
+
+    fn and(&mut self) -> Result<Expression> {
+        let mut expressions = vec![self.primary()?];
+        while self.keyword("and") {
+            expressions.push(self.primary()?);
+        }
+        Ok(Self::logical(LogicalOperator::And, expressions))
+    }
+
+    fn logical(operator: LogicalOperator, mut expressions: Vec<Expression>) -> Expression {
+        match expressions.len() {
+            1 => expressions.remove(0),
+            _ => Expression::Logical {
+                id: String::new(),
+                operator,
+                expressions,
+            },
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expression> {
+        if self.eat("(") {
+            return self.nested(self.pos - 1, |parser| {
+                let expression = parser.or()?;
+                parser.expect(")")?;
+                Ok(expression)
+            });
+        }
+
+        if let Some(span) = self.peek_identifier() {
+            let operator = ComparisonOperator::named(&self.source[span.to_owned()]);
+            if let (Some(operator), true) = (operator, self.source[span.end..].starts_with('(')) {
+                self.pos = span.end + 1;
+                return Ok(Expression::Comparison {
+                    id: String::new(),
+                    operator,
+                    operands: self.nested(span.end, Self::arguments)?,
+                });
+            }
+        }
+
+        let left = self.operand()?;
+        let operator = if self.eat("==") {
+            ComparisonOperator::Equals
+        } else if self.eat("!=") {
+            ComparisonOperator::NotEquals
+        } else if self.eat(">=") {
+            ComparisonOperator::GreaterThanOrEqual
+        } else if self.eat("<=") {
+            ComparisonOperator::LessThanOrEqual
+        } else if self.eat(">") {
+            ComparisonOperator::GreaterThan
+        } else if self.eat("<") {
+            ComparisonOperator::LessThan
+        } else if self.keyword("in") {
+            ComparisonOperator::Contains
+        } else if self.keyword("not") {
+            if !self.keyword("in") {
+                return Err(self.unexpected("`in`"));
+            }
+            ComparisonOperator::NotContains
+        } else {
+            return Err(self.unexpected("comparison operator"));
+        };
+
+        Ok(Expression::Comparison {
+            id: String::new(),
+            operator,
+            operands: vec![left, self.operand()?],
+        })
+    }
+
+    /// Comma separated operands up to the closing parenthesis, the opening
+    /// one being already consumed.
+    fn arguments(&mut self) -> Result<Vec<FieldDefinition>> {
+        let mut arguments = Vec::new();
+        if self.eat(")") {
+            return Ok(arguments);
+        }
+
+        loop {
+            arguments.push(self.operand()?);
+            if self.eat(")") {
+                return Ok(arguments);
+            }
+            if !self.eat(",") {
+                return Err(self.unexpected("`,` or `)`"));
+            }
+        }
+    }
+
+    fn operand(&mut self) -> Result<FieldDefinition> {
+        let Some(span) = self.peek_identifier() else {
+            return match self.peek() {
+                Some('"' | '[' | '{' | '-' | '0'..='9') => Ok(FieldDefinition::Value {
+                    value: self.literal()?,
+                }),
+                _ => Err(self.unexpected("operand")),
+            };
+        };
+
+        let name = &self.source[span.to_owned()];
+        if self.source[span.end..].starts_with('(') {
+            let function = Function::named(name).ok_or_else(|| ParseError {
+                message: format!("Unknown function `{}`", name),
+                span: span.to_owned(),
+            })?;
+            self.pos = span.end + 1;
+            return Ok(FieldDefinition::Function {
+                function,
+                args: self.nested(span.end, Self::arguments)?,
+            });
+        }
+
+        let field = match name {
+            "true" | "false" | "null" => {
+                return Ok(FieldDefinition::Value {
+                    value: self.literal()?,
+                })
+            }
+            "data" => {
+                self.pos = span.end;
+                FieldDefinition::Data { path: self.path()? }
+            }
+            "item" => {
+                self.pos = span.end;
+                FieldDefinition::Item { path: self.path()? }
+            }
+            "index" => {
+                self.pos = span.end;
+                FieldDefinition::Index
+            }
+            "var" => {
+                self.pos = span.end;
+                let variable = self
+                    .segment()?
+                    .ok_or_else(|| self.unexpected("variable name"))?;
+                FieldDefinition::Variable { variable }
+            }
+            "output" => {
+                self.pos = span.end;
+                let output_id = self
+                    .segment()?
+                    .ok_or_else(|| self.unexpected("component id"))?;
+                FieldDefinition::Output {
+                    output_id,
+                    path: self.path()?,
+                }
+            }
+            _ => {
+                return Err(ParseError {
+                    message: format!(
+                        "Unknown field `{}`, expected `data`, `var`, `output`, `item`, `index` or a function",
+                        name
+                    ),
+                    span,
+                })
+            }
+        };
+
+        Ok(field)
+    }
+
+    /// JSON pointer built from the segments directly following the cursor.
+    fn path(&mut self) -> Result<String> {
+        let mut path = String::new();
+        while let Some(segment) = self.segment()? {
+            path.push('/');
+            path.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+        }
+        Ok(path)
+    }
+
+    /// A `.name`, `.0`, `["any key"]` or `[0]` segment, which must directly
+    /// follow the previous token.
+    fn segment(&mut self) -> Result<Option<String>> {
+        let rest = self.rest();
+        if let Some(rest) = rest.strip_prefix('.') {
+            let len = rest.find(|char| !is_identifier(char)).unwrap_or(rest.len());
+            if len == 0 {
+                self.pos += 1;
+                return Err(self.unexpected("field name"));
+            }
+            let segment = rest[..len].to_owned();
+            self.pos += 1 + len;
+            return Ok(Some(segment));
+        }
+
+        if !rest.starts_with('[') {
+            return Ok(None);
+        }
+        self.pos += 1;
+        self.skip_whitespace();
+        let start = self.pos;
+        let segment = match self.peek() {
+            Some('"' | '0'..='9') => match self.literal()? {
+                Value::String(segment) => segment,
+                Value::Number(number) if number.is_u64() => number.to_string(),
+                _ => {
+                    return Err(ParseError {
+                        message: "Expected string or index".to_owned(),
+                        span: start..self.pos,
+                    })
+                }
+            },
+            _ => return Err(self.unexpected("string or index")),
+        };
+        self.expect("]")?;
+        Ok(Some(segment))
+    }
+
+    /// A JSON literal. Numbers are delimited by hand since the JSON stream
+    /// parser rejects numbers directly followed by `)`.
+    fn literal(&mut self) -> Result<Value> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let len = match rest.chars().next() {
+            Some('-' | '0'..='9') => rest
+                .find(|char: char| !(char.is_ascii_alphanumeric() || "+-.".contains(char)))
+                .unwrap_or(rest.len()),
+            Some(char) if is_identifier_start(char) => {
+                rest.find(|char| !is_identifier(char)).unwrap_or(rest.len())
+            }
+            _ => {
+                let mut stream = serde_json::Deserializer::from_str(rest).into_iter::<Value>();
+                match stream.next() {
+                    Some(Ok(_)) => stream.byte_offset(),
+                    Some(Err(err)) => {
+                        let offset = offset(rest, err.line(), err.column());
+                        let message = err.to_string();
+                        let message = message.split(" at line ").next().unwrap_or_default();
+                        return Err(ParseError {
+                            message: format!("Invalid literal: {}", message),
+                            span: self.pos + offset..(self.pos + offset + 1).min(self.source.len()),
+                        });
+                    }
+                    None => 0,
+                }
+            }
+        };
+
+        let span = self.pos..self.pos + len;
+        let value =
+            serde_json::from_str(&self.source[span.to_owned()]).map_err(|_| ParseError {
+                message: format!("Invalid literal `{}`", &self.source[span.to_owned()]),
+                span: span.to_owned(),
+            })?;
+        self.pos = span.end;
+        Ok(value)
+    }
+}
+
+/// Byte offset of a one-based line and column as reported by `serde_json`,
+/// clamped to the end of `source`.
+fn offset(source: &str, line: usize, column: usize) -> usize {
+    let line_start = source
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    (line_start + column.saturating_sub(1)).min(source.len())
+}
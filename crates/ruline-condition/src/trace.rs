@@ -0,0 +1,26 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::ComparisonOperator;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Branch {
+    Results,
+    Fallbacks,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ComparisonTrace {
+    pub id: String,
+    pub operator: ComparisonOperator,
+    pub operands: Vec<Value>,
+    pub result: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConditionTrace {
+    pub branch: Branch,
+    pub matched: Vec<String>,
+    pub comparisons: Vec<ComparisonTrace>,
+}
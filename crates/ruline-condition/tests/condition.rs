@@ -4,7 +4,7 @@ extern crate pretty_assertions;
 
 use dashmap::DashMap;
 use insta::assert_snapshot;
-use ruline_condition::{assert_condition_deserialize_error, Condition};
+use ruline_condition::{assert_condition_deserialize_error, Branch, Condition};
 use ruline_context::Context;
 use serde::Deserialize;
 use serde_json::json;
@@ -500,6 +500,84 @@ fn test_decision_fallback() {
     assert_eq!(result, vec!["0"]);
 }
 
+#[test]
+fn test_decision_nested_expression_first() {
+    let data = json!({
+        "first_value": 42,
+        "second_value": 30,
+    });
+    let context = Context::new(data, DashMap::new());
+
+    let definition = json!({
+        "type":"decision",
+        "fallbacks":[ "0" ],
+        "results":{
+            "100":[ "1" ],
+            "200":[ "2" ]
+        },
+        "expressions":[
+        {
+            "id":"100",
+            "type":"logical",
+            "operator":"or",
+            "expressions":[
+            {
+                "id":"101",
+                "type":"comparison",
+                "operator":"equals",
+                "operands":[
+                {
+                    "type":"data",
+                    "path":"/first_value"
+                },
+                {
+                    "type":"value",
+                    "value": 40
+                }
+                ]
+            },
+            {
+                "id":"102",
+                "type":"comparison",
+                "operator":"equals",
+                "operands":[
+                {
+                    "type":"data",
+                    "path":"/second_value"
+                },
+                {
+                    "type":"value",
+                    "value": 40
+                }
+                ]
+            }
+            ]
+        },
+        {
+            "id":"200",
+            "type":"comparison",
+            "operator":"equals",
+            "operands":[
+            {
+                "type":"data",
+                "path":"/second_value"
+            },
+            {
+                "type":"value",
+                "value": 30
+            }
+            ]
+        }
+        ]
+    });
+
+    let condition = Condition::try_from(definition).unwrap();
+    assert!(condition.validate().is_ok());
+
+    let result = condition.evaluate(&context).unwrap();
+    assert_eq!(result, vec!["2"]);
+}
+
 #[test]
 fn test_binary() {
     let data = json!({
@@ -745,6 +823,143 @@ fn test_binary_fallback() {
     assert_eq!(result, vec!["0"]);
 }
 
+#[test]
+fn test_binary_trace() {
+    let data = json!({
+        "first_value": 42,
+        "second_value": 30,
+    });
+    let context = Context::new(data, DashMap::new());
+
+    let definition = json!({
+        "type":"binary",
+        "fallbacks":[ "0" ],
+        "results": [ "1" ],
+        "expression":{
+            "id":"300",
+            "type":"logical",
+            "operator":"or",
+            "expressions":[
+            {
+                "id":"301",
+                "type":"comparison",
+                "operator":"greater_than",
+                "operands":[
+                {
+                    "type":"data",
+                    "path":"/first_value"
+                },
+                {
+                    "type":"value",
+                    "value": 50
+                }
+                ]
+            },
+            {
+                "id":"302",
+                "type":"comparison",
+                "operator":"equals",
+                "operands":[
+                {
+                    "type":"data",
+                    "path":"/second_value"
+                },
+                {
+                    "type":"value",
+                    "value": 30
+                }
+                ]
+            }
+            ]
+        }
+    });
+
+    let condition = Condition::try_from(definition).unwrap();
+    let (result, trace) = condition.evaluate_with_trace(&context).unwrap();
+    assert_eq!(result, vec!["1"]);
+    assert_eq!(
+        serde_json::to_value(trace).unwrap(),
+        json!({
+            "branch": "results",
+            "matched": [ "300" ],
+            "comparisons": [
+            {
+                "id": "301",
+                "operator": "greater_than",
+                "operands": [ 42, 50 ],
+                "result": false
+            },
+            {
+                "id": "302",
+                "operator": "equals",
+                "operands": [ 30, 30 ],
+                "result": true
+            }
+            ]
+        })
+    );
+}
+
+#[test]
+fn test_decision_trace_fallback() {
+    let context = Context::new(json!({ "value": 10 }), DashMap::new());
+
+    let definition = json!({
+        "type":"decision",
+        "fallbacks":[ "0" ],
+        "results":{
+            "100":[ "1" ],
+            "200":[ "2" ]
+        },
+        "expressions":[
+        {
+            "id":"100",
+            "type":"comparison",
+            "operator":"equals",
+            "operands":[
+            {
+                "type":"data",
+                "path":"/value"
+            },
+            {
+                "type":"value",
+                "value": 20
+            }
+            ]
+        },
+        {
+            "id":"200",
+            "type":"comparison",
+            "operator":"greater_than",
+            "operands":[
+            {
+                "type":"data",
+                "path":"/value"
+            },
+            {
+                "type":"value",
+                "value": 20
+            }
+            ]
+        }
+        ]
+    });
+
+    let condition = Condition::try_from(definition).unwrap();
+    let (result, trace) = condition.evaluate_with_trace(&context).unwrap();
+    assert_eq!(result, vec!["0"]);
+    assert_eq!(trace.branch, Branch::Fallbacks);
+    assert!(trace.matched.is_empty());
+    assert_eq!(
+        trace
+            .comparisons
+            .iter()
+            .map(|comparison| (comparison.id.as_str(), comparison.result))
+            .collect::<Vec<_>>(),
+        vec![("100", false), ("200", false)]
+    );
+}
+
 #[test]
 fn test_dependencies() {
     let definition = json!({
@@ -1064,3 +1279,61 @@ fn test_deserialize_error_invalid_logical_expressions_type() {
     });
     assert_condition_deserialize_error!(definition);
 }
+
+#[test]
+fn test_condition_export() {
+    let condition = Condition::try_from(json!({
+        "type": "binary",
+        "fallbacks": [],
+        "results": [],
+        "expression": {
+            "id": "1",
+            "type": "logical",
+            "operator": "and",
+            "expressions": [
+            {
+                "id": "2",
+                "type": "comparison",
+                "operator": "greater_than_or_equal",
+                "operands": [
+                { "type": "data", "path": "/age" },
+                { "type": "value", "value": 18 }
+                ]
+            },
+            {
+                "id": "3",
+                "type": "logical",
+                "operator": "or",
+                "expressions": [
+                {
+                    "id": "4",
+                    "type": "comparison",
+                    "operator": "contains",
+                    "operands": [
+                    { "type": "variable", "variable": "country" },
+                    { "type": "value", "value": ["AR", "UY"] }
+                    ]
+                },
+                {
+                    "id": "5",
+                    "type": "comparison",
+                    "operator": "exists",
+                    "operands": [{ "type": "data", "path": "/vip" }]
+                }
+                ]
+            }
+            ]
+        }
+    }))
+    .unwrap();
+
+    match condition.definition() {
+        ruline_condition::ConditionDefinition::Binary { expression, .. } => assert_eq!(
+            expression.to_string(),
+            "data.age >= 18 and (var.country in [\"AR\",\"UY\"] or exists(data.vip))"
+        ),
+        definition => panic!("unexpected definition {:?}", definition),
+    }
+    assert_snapshot!("condition_export_dot", condition.to_dot());
+    assert_snapshot!("condition_export_mermaid", condition.to_mermaid());
+}
@@ -0,0 +1,264 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use anyhow::Result;
+use dashmap::DashMap;
+use insta::assert_snapshot;
+use ruline_condition::{Arity, Condition, CustomOperator, Expression, OperatorRegistry};
+use ruline_context::Context;
+use ruline_field::{CustomFunction, FieldType, FunctionRegistry, Signature, TypeEnv};
+use serde_json::{json, Value};
+
+#[derive(Debug)]
+struct StartsWith;
+
+impl CustomOperator for StartsWith {
+    fn arity(&self) -> Arity {
+        Arity::Exact(2)
+    }
+
+    fn check(&self, operands: &[FieldType]) -> bool {
+        operands.iter().all(|operand| operand.is(FieldType::String))
+    }
+
+    fn eval(&self, operands: &[Value]) -> Result<bool> {
+        match (&operands[0], &operands[1]) {
+            (Value::String(value), Value::String(prefix)) => Ok(value.starts_with(prefix)),
+            _ => Ok(false),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct AllTrue;
+
+impl CustomOperator for AllTrue {
+    fn arity(&self) -> Arity {
+        Arity::AtLeast(1)
+    }
+
+    fn eval(&self, operands: &[Value]) -> Result<bool> {
+        Ok(operands.iter().all(|operand| operand == &Value::Bool(true)))
+    }
+}
+
+#[derive(Debug)]
+struct Len;
+
+impl CustomFunction for Len {
+    fn signature(&self) -> Signature {
+        Signature::new(vec![FieldType::String], FieldType::Number)
+    }
+
+    fn call(&self, args: Vec<Value>) -> Result<Value> {
+        Ok(json!(args[0].as_str().map_or(0, str::len)))
+    }
+}
+
+fn operators() -> OperatorRegistry {
+    OperatorRegistry::default()
+        .register_negated("starts_with", "not_starts_with", StartsWith)
+        .unwrap()
+        .register("all_true", AllTrue)
+        .unwrap()
+}
+
+fn condition(operator: &str, operands: Value) -> Condition {
+    // `ends_with` deserializes but is missing from the registry the condition
+    // is built with.
+    let known = operators().register("ends_with", StartsWith).unwrap();
+    known
+        .scope(|| {
+            Condition::try_from(json!({
+                "type": "binary",
+                "fallbacks": [ "0" ],
+                "results": [ "1" ],
+                "expression": {
+                    "id": "100",
+                    "type": "comparison",
+                    "operator": operator,
+                    "operands": operands
+                }
+            }))
+        })
+        .unwrap()
+        .with_operators(&operators())
+}
+
+fn evaluate(condition: &Condition) -> Result<Vec<String>> {
+    let data = json!({ "code": "AR-123", "flags": [true, true, false] });
+    condition.evaluate(&Context::new(data, DashMap::new()))
+}
+
+#[test]
+fn test_operator_eval() {
+    let prefix = |prefix: &str| {
+        json!([
+            { "type": "data", "path": "/code" },
+            { "type": "value", "value": prefix }
+        ])
+    };
+
+    let condition = condition("starts_with", prefix("AR"));
+    assert!(condition.validate().is_ok());
+    assert_eq!(evaluate(&condition).unwrap(), vec!["1"]);
+    assert_eq!(
+        evaluate(&self::condition("starts_with", prefix("UY"))).unwrap(),
+        vec!["0"]
+    );
+    assert_eq!(
+        evaluate(&self::condition("not_starts_with", prefix("UY"))).unwrap(),
+        vec!["1"]
+    );
+
+    let condition = self::condition(
+        "all_true",
+        json!([
+            { "type": "data", "path": "/flags/0" },
+            { "type": "data", "path": "/flags/1" },
+            { "type": "value", "value": true }
+        ]),
+    );
+    assert_eq!(evaluate(&condition).unwrap(), vec!["1"]);
+}
+
+#[test]
+fn test_operator_trace() {
+    let condition = condition(
+        "not_starts_with",
+        json!([
+            { "type": "data", "path": "/code" },
+            { "type": "value", "value": "AR" }
+        ]),
+    );
+    let context = Context::new(json!({ "code": "AR-123" }), DashMap::new());
+    let (_, trace) = condition.evaluate_with_trace(&context).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&trace.comparisons).unwrap(),
+        json!([{
+            "id": "100",
+            "operator": "not_starts_with",
+            "operands": ["AR-123", "AR"],
+            "result": false
+        }])
+    );
+}
+
+#[test]
+fn test_operator_validate() {
+    let errors = [
+        condition("all_true", json!([])),
+        condition("starts_with", json!([{ "type": "data", "path": "/code" }])),
+        condition("ends_with", json!([{ "type": "data", "path": "/code" }])),
+        operators()
+            .scope(|| {
+                Condition::try_from(json!({
+                    "type": "binary",
+                    "fallbacks": [],
+                    "results": [],
+                    "expression": {
+                        "id": "100",
+                        "type": "comparison",
+                        "operator": "starts_with",
+                        "operands": []
+                    }
+                }))
+            })
+            .unwrap(),
+    ]
+    .iter()
+    .map(|condition| condition.validate().unwrap_err().to_string())
+    .collect::<Vec<_>>();
+
+    assert_snapshot!(errors.join("\n"));
+}
+
+#[test]
+fn test_operator_eval_error() {
+    let errors = [
+        condition("all_true", json!([])),
+        condition("ends_with", json!([{ "type": "data", "path": "/code" }])),
+    ]
+    .iter()
+    .map(|condition| evaluate(condition).unwrap_err().to_string())
+    .collect::<Vec<_>>();
+
+    assert_snapshot!(errors.join("\n"));
+}
+
+struct Env;
+
+impl TypeEnv for Env {
+    fn data_type(&self, path: &str) -> FieldType {
+        match path {
+            "/code" => FieldType::String,
+            "/age" => FieldType::Number,
+            _ => FieldType::Any,
+        }
+    }
+}
+
+#[test]
+fn test_operator_check_types() {
+    let condition = condition(
+        "starts_with",
+        json!([
+            { "type": "data", "path": "/age" },
+            { "type": "value", "value": "1" }
+        ]),
+    );
+
+    let mismatches = condition
+        .check_types(&Env)
+        .into_iter()
+        .map(|(id, err)| format!("{}: {}", id, err))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        mismatches,
+        vec!["100: `starts_with` cannot compare operands of type `number`, `string`"]
+    );
+}
+
+#[test]
+fn test_operator_register_invalid() {
+    let errors = [
+        OperatorRegistry::default().register("equals", AllTrue),
+        OperatorRegistry::default().register("all-true", AllTrue),
+        OperatorRegistry::default().register_negated("starts_with", "not_empty", StartsWith),
+    ]
+    .into_iter()
+    .map(|registry| registry.unwrap_err().to_string())
+    .collect::<Vec<_>>();
+
+    assert_snapshot!(errors.join("\n"));
+}
+
+#[test]
+fn test_operator_any_name() {
+    let parse = || Expression::parse("1", "ends_with(data.code, \"AR\")");
+
+    assert!(parse().is_err());
+    let expression = OperatorRegistry::any_name(parse).unwrap();
+    assert_eq!(expression.to_string(), "ends_with(data.code, \"AR\")");
+    assert!(parse().is_err());
+}
+
+#[test]
+fn test_operator_parse() {
+    let functions = FunctionRegistry::default().register("len", Len).unwrap();
+    functions.scope(|| operators().scope(|| {
+        let expression = Expression::parse(
+            "1",
+            r#"starts_with(data.code, "AR") and len(data.code) > 3 or not_starts_with(upper(data.code), var.prefix)"#,
+        )
+        .unwrap();
+
+        assert_snapshot!(serde_json::to_string_pretty(&expression).unwrap());
+
+        let printed = expression.to_string();
+        let reparsed = Expression::parse("1", &printed).unwrap();
+        assert_eq!(reparsed.to_string(), printed);
+    }));
+}
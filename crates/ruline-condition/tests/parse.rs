@@ -0,0 +1,190 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use anyhow::Result;
+use insta::assert_snapshot;
+use ruline_condition::{parse_field, Expression};
+use ruline_field::{CustomFunction, FieldType, FunctionRegistry, Signature};
+use serde_json::{json, Value};
+
+#[derive(Debug)]
+struct RiskScore;
+
+impl CustomFunction for RiskScore {
+    fn signature(&self) -> Signature {
+        Signature::new(vec![FieldType::Any, FieldType::String], FieldType::Number)
+    }
+
+    fn call(&self, _: Vec<Value>) -> Result<Value> {
+        Ok(json!(0.5))
+    }
+}
+
+fn parse(source: &str) -> Value {
+    serde_json::to_value(Expression::parse("1", source).unwrap()).unwrap()
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        parse(r#"data.age >= 18 and (var.country in ["AR","UY"] or exists(data.vip))"#),
+        json!({
+            "id": "1",
+            "type": "logical",
+            "operator": "and",
+            "expressions": [
+                {
+                    "id": "1.1",
+                    "type": "comparison",
+                    "operator": "greater_than_or_equal",
+                    "operands": [
+                        { "type": "data", "path": "/age" },
+                        { "type": "value", "value": 18 }
+                    ]
+                },
+                {
+                    "id": "1.2",
+                    "type": "logical",
+                    "operator": "or",
+                    "expressions": [
+                        {
+                            "id": "1.2.1",
+                            "type": "comparison",
+                            "operator": "contains",
+                            "operands": [
+                                { "type": "variable", "variable": "country" },
+                                { "type": "value", "value": [ "AR", "UY" ] }
+                            ]
+                        },
+                        {
+                            "id": "1.2.2",
+                            "type": "comparison",
+                            "operator": "exists",
+                            "operands": [ { "type": "data", "path": "/vip" } ]
+                        }
+                    ]
+                }
+            ]
+        })
+    );
+}
+
+#[test]
+fn test_parse_precedence() {
+    assert_eq!(
+        Expression::parse(
+            "1",
+            "data.a == 1 or data.b == 2 and data.c == 3 or data.d == 4"
+        )
+        .unwrap()
+        .to_string(),
+        "data.a == 1 or (data.b == 2 and data.c == 3) or data.d == 4"
+    );
+    assert_eq!(
+        Expression::parse("1", "((data.a == 1))")
+            .unwrap()
+            .to_string(),
+        "data.a == 1"
+    );
+}
+
+#[test]
+fn test_parse_field() {
+    assert_eq!(
+        serde_json::to_value(parse_field("add(data.a, 2)").unwrap()).unwrap(),
+        json!({
+            "type": "function",
+            "function": "add",
+            "args": [
+                { "type": "data", "path": "/a" },
+                { "type": "value", "value": 2 }
+            ]
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(parse_field(r#"output["1"]["fee/total"][0]"#).unwrap()).unwrap(),
+        json!({ "type": "output", "output_id": "1", "path": "/fee~1total/0" })
+    );
+    assert_eq!(
+        serde_json::to_value(parse_field("-1.5e2").unwrap()).unwrap(),
+        json!({ "type": "value", "value": -150.0 })
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let sources = [
+        r#"data.age >= 18 and (var.country in ["AR","UY"] or exists(data.vip))"#,
+        r#"output["1"].fee <= sub(data.limit, 10) and data.tags not in [1,2]"#,
+        r#"item.name != null or (index < 3 and var.flag == true)"#,
+        r#"data["first name"] == {"a":[1,2]} or empty(data.list)"#,
+        r#"risk_score(data.id, upper(var.region)) > 0.5"#,
+    ];
+
+    let functions = FunctionRegistry::default()
+        .register("risk_score", RiskScore)
+        .unwrap();
+    functions.scope(|| {
+        for source in sources {
+            let expression = Expression::parse("1", source).unwrap();
+            let printed = expression.to_string();
+            let reparsed = Expression::parse("1", &printed).unwrap();
+
+            assert_eq!(reparsed.to_string(), printed);
+            assert_eq!(
+                serde_json::to_value(reparsed).unwrap(),
+                serde_json::to_value(expression).unwrap()
+            );
+        }
+    });
+}
+
+#[test]
+fn test_parse_errors() {
+    let errors = [
+        "data.age >=",
+        "data.age 18",
+        "data.age >= 18 and",
+        "(data.a == 1",
+        "data.a == 1)",
+        "foo(data.a) == 1",
+        "upper(data.a == 1",
+        "user.age == 1",
+        "data.a == [1, 2",
+        "data.a not 1",
+        "var == 1",
+        "data.a.",
+        "exists(data.a data.b)",
+    ];
+
+    let rendered = errors
+        .iter()
+        .map(|source| {
+            let err = Expression::parse("1", source).unwrap_err();
+            format!(
+                "{}\n{}{}\n{}\n",
+                source,
+                " ".repeat(err.span.start),
+                "^".repeat((err.span.end - err.span.start).max(1)),
+                err
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_snapshot!(rendered);
+}
+
+#[test]
+fn test_parse_nesting_depth() {
+    let parenthesized = |depth| format!("{}data.a == 1{}", "(".repeat(depth), ")".repeat(depth));
+    let called = |depth| format!("{}data.a{} == 1", "abs(".repeat(depth), ")".repeat(depth));
+
+    assert!(Expression::parse("1", &parenthesized(64)).is_ok());
+    assert!(Expression::parse("1", &called(64)).is_ok());
+
+    let err = Expression::parse("1", &parenthesized(65)).unwrap_err();
+    assert_eq!(err.to_string(), "Nesting deeper than 64 levels at 64..65");
+    let err = Expression::parse("1", &called(100_000)).unwrap_err();
+    assert_eq!(err.to_string(), "Nesting deeper than 64 levels at 259..260");
+}
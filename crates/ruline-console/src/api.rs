@@ -91,7 +91,7 @@ pub fn router(app: Arc<App>) -> Router {
                         span.record("server.port", port);
                     }
                     let protocol_str = format!("{:?}", request.version());
-                    let protocol_version = protocol_str.split('/').last();
+                    let protocol_version = protocol_str.split('/').next_back();
                     if let Some(protocol_version) = protocol_version {
                         span.record("network.protocol.version", protocol_version);
                     }
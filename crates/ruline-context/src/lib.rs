@@ -6,6 +6,7 @@ pub struct Context {
     pub data: Value,
     pub outputs: DashMap<String, Value>,
     pub variables: DashMap<String, Value>,
+    pub item: Option<(usize, Value)>,
 }
 
 impl Context {
@@ -14,9 +15,16 @@ impl Context {
             data,
             outputs: DashMap::new(),
             variables,
+            item: None,
         }
     }
 
+    /// Sets the element and index of the array currently being iterated.
+    pub fn with_item(mut self, index: usize, item: Value) -> Self {
+        self.item = Some((index, item));
+        self
+    }
+
     pub fn set_output(&self, id: String, value: Value) {
         self.outputs.insert(id, value);
     }
@@ -29,6 +37,16 @@ impl Context {
         self.outputs.get(id).and_then(|v| v.pointer(key).cloned())
     }
 
+    pub fn get_item(&self, key: &str) -> Option<Value> {
+        self.item
+            .as_ref()
+            .and_then(|(_, item)| item.pointer(key).cloned())
+    }
+
+    pub fn get_index(&self) -> Option<Value> {
+        self.item.as_ref().map(|(index, _)| Value::from(*index))
+    }
+
     pub fn get_variable(&self, key: &str) -> Option<Value> {
         self.variables.get(key).map(|v| v.value().to_owned())
     }
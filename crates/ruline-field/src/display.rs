@@ -0,0 +1,62 @@
+use std::fmt::{self, Display, Write};
+
+use serde_json::Value;
+
+use crate::FieldDefinition;
+
+fn is_identifier(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    matches!(chars.next(), Some(char) if char.is_ascii_alphabetic() || char == '_')
+        && chars.all(|char| char.is_ascii_alphanumeric() || char == '_')
+}
+
+/// Writes `segment` as `.segment`, or as `["segment"]` when it is not a plain
+/// identifier or array index.
+fn write_segment(f: &mut fmt::Formatter<'_>, segment: &str) -> fmt::Result {
+    if is_identifier(segment)
+        || (!segment.is_empty() && segment.chars().all(|char| char.is_ascii_digit()))
+    {
+        write!(f, ".{}", segment)
+    } else {
+        write!(f, "[{}]", Value::from(segment))
+    }
+}
+
+fn write_path(f: &mut fmt::Formatter<'_>, root: &str, path: &str) -> fmt::Result {
+    f.write_str(root)?;
+    for segment in path.split('/').skip(1) {
+        write_segment(f, &segment.replace("~1", "/").replace("~0", "~"))?;
+    }
+    Ok(())
+}
+
+/// Renders fields in the textual expression syntax, e.g. `data.age`,
+/// `var.country`, `output["1"].fee` or `add(data.a, 2)`.
+impl Display for FieldDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldDefinition::Variable { variable } => {
+                f.write_str("var")?;
+                write_segment(f, variable)
+            }
+            FieldDefinition::Data { path } => write_path(f, "data", path),
+            FieldDefinition::Output { output_id, path } => {
+                write!(f, "output[{}]", Value::from(output_id.as_str()))?;
+                write_path(f, "", path)
+            }
+            FieldDefinition::Item { path } => write_path(f, "item", path),
+            FieldDefinition::Index => f.write_str("index"),
+            FieldDefinition::Value { value } => write!(f, "{}", value),
+            FieldDefinition::Function { function, args } => {
+                write!(f, "{}(", function)?;
+                for (index, arg) in args.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                f.write_char(')')
+            }
+        }
+    }
+}
@@ -20,6 +20,11 @@ pub enum FieldError {
             "`{}` in output `{}` not found",
             path, output_id
         ),
+        FieldDefinition::Item { path } => format!(
+            "`{}` in item not found",
+            path
+        ),
+        FieldDefinition::Index => "Index not found".to_owned(),
         _ => "Value not found".to_owned(),
     })]
     FieldNotFound(FieldDefinition),
@@ -11,6 +11,14 @@ pub enum FunctionError {
     },
     #[error("Argument type invalid")]
     ArgumentTypeInvalid,
+    #[error("Function `{0}` not found")]
+    FunctionNotFound(String),
+    #[error("Function `{0}` is async and can only be called from async processing")]
+    FunctionAsync(String),
+    #[error("Function name `{0}` is not an identifier")]
+    FunctionNameInvalid(String),
+    #[error("Function name `{0}` is reserved for a built-in function")]
+    FunctionNameReserved(String),
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
 }
@@ -1,12 +1,23 @@
+use std::fmt::{self, Display};
+
 use anyhow::Result;
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{self, EnumAccess, VariantAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
 use serde_json::Value;
 
 mod error;
 mod func;
+mod registry;
+mod signature;
 mod validate;
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+pub use error::FunctionError;
+pub(crate) use registry::{call, call_async, Callable};
+pub use registry::{AsyncFunction, CustomFunction, FunctionRegistry, Signature};
+
+#[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum Function {
     Add,
@@ -23,9 +34,96 @@ pub enum Function {
     Upper,
     Lower,
     Join,
+    /// A function looked up by name in a [`FunctionRegistry`]. Only the names
+    /// of the registry in [`FunctionRegistry::scope`] deserialize into one.
+    #[serde(untagged)]
+    Custom(String),
+}
+
+impl Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Function::Add => "add",
+            Function::Sub => "sub",
+            Function::Mul => "mul",
+            Function::Div => "div",
+            Function::Mod => "mod",
+            Function::Pow => "pow",
+            Function::Min => "min",
+            Function::Max => "max",
+            Function::Abs => "abs",
+            Function::Mean => "mean",
+            Function::Median => "median",
+            Function::Upper => "upper",
+            Function::Lower => "lower",
+            Function::Join => "join",
+            Function::Custom(name) => name,
+        };
+        f.write_str(name)
+    }
+}
+
+const BUILT_IN: &[&str] = &[
+    "add", "sub", "mul", "div", "mod", "pow", "min", "max", "abs", "mean", "median", "upper",
+    "lower", "join",
+];
+
+impl<'de> Deserialize<'de> for Function {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_enum("Function", BUILT_IN, FunctionVisitor)
+    }
+}
+
+struct FunctionVisitor;
+
+impl<'de> Visitor<'de> for FunctionVisitor {
+    type Value = Function;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("enum Function")
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Function, A::Error> {
+        let (name, variant): (String, _) = data.variant()?;
+        variant.unit_variant()?;
+        Function::named(&name).ok_or_else(|| de::Error::unknown_variant(&name, BUILT_IN))
+    }
 }
 
 impl Function {
+    /// Returns the built-in function named `name`, or else the custom one
+    /// registered under it in the registry in scope.
+    pub fn named(name: &str) -> Option<Self> {
+        match Self::built_in(name) {
+            Some(function) => Some(function),
+            None if registry::in_scope(name) => Some(Function::Custom(name.to_owned())),
+            None => None,
+        }
+    }
+
+    pub(crate) fn built_in(name: &str) -> Option<Self> {
+        let function = match name {
+            "add" => Function::Add,
+            "sub" => Function::Sub,
+            "mul" => Function::Mul,
+            "div" => Function::Div,
+            "mod" => Function::Mod,
+            "pow" => Function::Pow,
+            "min" => Function::Min,
+            "max" => Function::Max,
+            "abs" => Function::Abs,
+            "mean" => Function::Mean,
+            "median" => Function::Median,
+            "upper" => Function::Upper,
+            "lower" => Function::Lower,
+            "join" => Function::Join,
+            _ => return None,
+        };
+        Some(function)
+    }
+
+    /// Runs a built-in function. Custom functions are resolved and called by
+    /// the fields built with a registry.
     pub fn process(&self, args: Vec<Value>) -> Result<Value> {
         match self {
             Function::Add => func::add(args),
@@ -42,6 +140,7 @@ impl Function {
             Function::Upper => func::upper(args),
             Function::Lower => func::lower(args),
             Function::Join => func::join(args),
+            Function::Custom(name) => Err(FunctionError::FunctionNotFound(name.to_owned()).into()),
         }
     }
 }
@@ -0,0 +1,214 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Debug, sync::Arc};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{error::FunctionError, Function};
+use crate::FieldType;
+
+/// Argument and result types of a custom function: the `args` it always
+/// takes, followed by any number of `variadic` ones when set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    pub args: Vec<FieldType>,
+    pub variadic: Option<FieldType>,
+    pub returns: FieldType,
+}
+
+impl Signature {
+    pub fn new(args: Vec<FieldType>, returns: FieldType) -> Self {
+        Self {
+            args,
+            variadic: None,
+            returns,
+        }
+    }
+
+    pub fn with_variadic(mut self, arg: FieldType) -> Self {
+        self.variadic = Some(arg);
+        self
+    }
+
+    /// Checks the argument types known before execution and returns the
+    /// result type.
+    pub fn check(&self, args: &[FieldType]) -> Result<FieldType> {
+        self.check_arity(args.len())?;
+
+        let expected = self.args.iter().chain(self.variadic.iter().cycle());
+        if args
+            .iter()
+            .zip(expected)
+            .all(|(arg, expected)| *expected == FieldType::Any || arg.is(*expected))
+        {
+            Ok(self.returns)
+        } else {
+            Err(FunctionError::ArgumentTypeInvalid.into())
+        }
+    }
+
+    fn check_arity(&self, received: usize) -> Result<()> {
+        let expected = self.args.len();
+        match self.variadic {
+            None if received != expected => {
+                Err(FunctionError::ArgumentsAmountMismatch { expected, received }.into())
+            }
+            Some(_) if received < expected => Err(FunctionError::ArgumentsAmountLessThanRequired {
+                min_required: expected,
+                received,
+            }
+            .into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A function provided by the host application, callable from fields by the
+/// name it is registered under.
+pub trait CustomFunction: Debug + Send + Sync {
+    fn signature(&self) -> Signature;
+
+    fn call(&self, args: Vec<Value>) -> Result<Value>;
+}
+
+/// A custom function that awaits, e.g. on a lookup over the network. Fields
+/// calling one can only be processed with `Field::process_async`.
+#[async_trait]
+pub trait AsyncFunction: Debug + Send + Sync {
+    fn signature(&self) -> Signature;
+
+    async fn call(&self, args: Vec<Value>) -> Result<Value>;
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Callable {
+    Sync(Arc<dyn CustomFunction>),
+    Async(Arc<dyn AsyncFunction>),
+}
+
+impl Callable {
+    fn signature(&self) -> Signature {
+        match self {
+            Callable::Sync(function) => function.signature(),
+            Callable::Async(function) => function.signature(),
+        }
+    }
+}
+
+/// Custom functions by name. Fields built with a registry resolve the custom
+/// functions they call once, when built, and check the arguments against
+/// the signature before every call.
+#[derive(Debug, Clone, Default)]
+pub struct FunctionRegistry {
+    functions: HashMap<String, Callable>,
+}
+
+impl FunctionRegistry {
+    /// Registers `function` under `name`, replacing any function registered
+    /// under it before. Names must be identifiers other than the ones of the
+    /// built-in functions.
+    pub fn register(
+        self,
+        name: &str,
+        function: impl CustomFunction + 'static,
+    ) -> Result<Self, FunctionError> {
+        self.insert(name, Callable::Sync(Arc::new(function)))
+    }
+
+    pub fn register_async(
+        self,
+        name: &str,
+        function: impl AsyncFunction + 'static,
+    ) -> Result<Self, FunctionError> {
+        self.insert(name, Callable::Async(Arc::new(function)))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.functions.contains_key(name)
+    }
+
+    pub fn signature(&self, name: &str) -> Option<Signature> {
+        self.functions.get(name).map(Callable::signature)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.functions.keys().map(String::as_str)
+    }
+
+    /// Runs `f` with the functions of the registry accepted by name wherever
+    /// a function is deserialized or parsed, which only accepts the built-in
+    /// ones otherwise. Workflows are built in the scope of their registry.
+    pub fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        enter(Scope::Registry(self.to_owned()), f)
+    }
+
+    /// Runs `f` accepting any function name, e.g. to find the variables read
+    /// by a definition regardless of the registry it was built with.
+    pub fn any_name<T>(f: impl FnOnce() -> T) -> T {
+        enter(Scope::Any, f)
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<Callable> {
+        self.functions.get(name).cloned()
+    }
+
+    fn insert(mut self, name: &str, function: Callable) -> Result<Self, FunctionError> {
+        let mut chars = name.chars();
+        let identifier = matches!(chars.next(), Some(char) if char.is_ascii_alphabetic() || char == '_')
+            && chars.all(|char| char.is_ascii_alphanumeric() || char == '_');
+        if !identifier {
+            return Err(FunctionError::FunctionNameInvalid(name.to_owned()));
+        }
+        if Function::built_in(name).is_some() {
+            return Err(FunctionError::FunctionNameReserved(name.to_owned()));
+        }
+
+        self.functions.insert(name.to_owned(), function);
+        Ok(self)
+    }
+}
+
+enum Scope {
+    Registry(FunctionRegistry),
+    /// Any identifier, for reading definitions whose registry is unknown.
+    Any,
+}
+
+thread_local! {
+    static SCOPE: RefCell<Option<Scope>> = const { RefCell::new(None) };
+}
+
+fn enter<T>(scope: Scope, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Scope>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPE.with(|scope| *scope.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(SCOPE.with(|current| current.replace(Some(scope))));
+    f()
+}
+
+/// Whether a custom function is registered under `name` in the scope.
+pub(crate) fn in_scope(name: &str) -> bool {
+    SCOPE.with(|scope| match &*scope.borrow() {
+        Some(Scope::Registry(functions)) => functions.contains(name),
+        Some(Scope::Any) => true,
+        None => false,
+    })
+}
+
+/// Calls `function` after checking the number and types of `args`.
+pub(crate) fn call(function: &dyn CustomFunction, args: Vec<Value>) -> Result<Value> {
+    let types = args.iter().map(FieldType::of).collect::<Vec<_>>();
+    function.signature().check(&types)?;
+    function.call(args)
+}
+
+pub(crate) async fn call_async(function: &dyn AsyncFunction, args: Vec<Value>) -> Result<Value> {
+    let types = args.iter().map(FieldType::of).collect::<Vec<_>>();
+    function.signature().check(&types)?;
+    function.call(args).await
+}
@@ -0,0 +1,69 @@
+use anyhow::Result;
+
+use super::{error::FunctionError, Function};
+use crate::{validate_args, validate_min_args, FieldType};
+
+macro_rules! expect_args {
+    ($args:expr, $expected:expr) => {
+        if !$args.iter().all(|arg| arg.is($expected)) {
+            return Err(FunctionError::ArgumentTypeInvalid.into());
+        }
+    };
+}
+
+fn numeric(args: &[FieldType]) -> Result<FieldType> {
+    validate_min_args!(args, 1);
+
+    match args.len() {
+        1 => expect_args!(args, FieldType::Array),
+        _ => expect_args!(args, FieldType::Number),
+    }
+    Ok(FieldType::Number)
+}
+
+fn binary(args: &[FieldType]) -> Result<FieldType> {
+    validate_args!(args, 2);
+
+    expect_args!(args, FieldType::Number);
+    Ok(FieldType::Number)
+}
+
+fn unary(args: &[FieldType], expected: FieldType) -> Result<FieldType> {
+    validate_args!(args, 1);
+
+    expect_args!(args, expected);
+    Ok(expected)
+}
+
+fn join(args: &[FieldType]) -> Result<FieldType> {
+    validate_min_args!(args, 2);
+
+    expect_args!(args[..1], FieldType::String);
+    if !args[1..]
+        .iter()
+        .all(|arg| arg.is(FieldType::String) || arg.is(FieldType::Array))
+    {
+        return Err(FunctionError::ArgumentTypeInvalid.into());
+    }
+    Ok(FieldType::String)
+}
+
+impl Function {
+    pub fn infer_type(&self, args: &[FieldType]) -> Result<FieldType> {
+        match self {
+            Function::Add
+            | Function::Sub
+            | Function::Mul
+            | Function::Div
+            | Function::Min
+            | Function::Max
+            | Function::Mean
+            | Function::Median => numeric(args),
+            Function::Mod | Function::Pow => binary(args),
+            Function::Abs => unary(args, FieldType::Number),
+            Function::Upper | Function::Lower => unary(args, FieldType::String),
+            Function::Join => join(args),
+            Function::Custom(name) => Err(FunctionError::FunctionNotFound(name.to_owned()).into()),
+        }
+    }
+}
@@ -1,13 +1,21 @@
 use anyhow::Result;
+use async_recursion::async_recursion;
 use error::FieldError::{self, FieldNotFound};
-use function::Function;
+use function::Callable;
+pub use function::{
+    AsyncFunction, CustomFunction, Function, FunctionError, FunctionRegistry, Signature,
+};
 use ruline_context::Context;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
+pub use types::{FieldType, TypeEnv};
+
+mod display;
 mod error;
 mod function;
 mod test;
+mod types;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "snake_case", tag = "type")]
@@ -15,6 +23,8 @@ pub enum FieldDefinition {
     Variable { variable: String },
     Data { path: String },
     Output { output_id: String, path: String },
+    Item { path: String },
+    Index,
     Value { value: Value },
     Function { function: Function, args: Vec<Self> },
 }
@@ -22,13 +32,14 @@ pub enum FieldDefinition {
 #[derive(Debug)]
 pub struct Field {
     pub definition: FieldDefinition,
+    children: Vec<Option<Field>>,
+    custom: Option<Callable>,
+    asynchronous: bool,
 }
 
 impl From<&FieldDefinition> for Field {
     fn from(definition: &FieldDefinition) -> Self {
-        Field {
-            definition: definition.to_owned(),
-        }
+        Self::with_functions(definition, &FunctionRegistry::default())
     }
 }
 
@@ -44,7 +55,152 @@ impl TryFrom<Value> for Field {
 }
 
 impl Field {
+    /// Builds the field resolving the custom functions it calls in
+    /// `functions`. Calls to functions missing from it fail when processed.
+    pub fn with_functions(definition: &FieldDefinition, functions: &FunctionRegistry) -> Self {
+        let children = match definition {
+            FieldDefinition::Function { args, .. } => args
+                .iter()
+                .map(|arg| Some(Self::with_functions(arg, functions)))
+                .collect(),
+            FieldDefinition::Value {
+                value: Value::Array(values),
+            } => values
+                .iter()
+                .map(|value| Self::nested(value, functions))
+                .collect(),
+            FieldDefinition::Value {
+                value: Value::Object(map),
+            } => map
+                .values()
+                .map(|value| Self::nested(value, functions))
+                .collect(),
+            _ => vec![],
+        };
+
+        let custom = match definition {
+            FieldDefinition::Function {
+                function: Function::Custom(name),
+                ..
+            } => functions.get(name),
+            _ => None,
+        };
+
+        let asynchronous = matches!(custom, Some(Callable::Async(_)))
+            || children.iter().flatten().any(Field::is_async);
+
+        Field {
+            definition: definition.to_owned(),
+            children,
+            custom,
+            asynchronous,
+        }
+    }
+
+    /// Whether the field, or any field nested in it, calls an async function.
+    pub fn is_async(&self) -> bool {
+        self.asynchronous
+    }
+
     pub fn process(&self, ctx: &Context) -> Result<Value> {
+        match &self.definition {
+            FieldDefinition::Value {
+                value: Value::Array(values),
+            } => values
+                .iter()
+                .zip(&self.children)
+                .map(|(value, field)| match field {
+                    Some(field) => field.process(ctx),
+                    None => Ok(value.to_owned()),
+                })
+                .collect::<Result<Vec<_>>>()
+                .map(Value::Array),
+            FieldDefinition::Value {
+                value: Value::Object(map),
+            } => map
+                .iter()
+                .zip(&self.children)
+                .map(|((key, value), field)| {
+                    let value = match field {
+                        Some(field) => field.process(ctx)?,
+                        None => value.to_owned(),
+                    };
+                    Ok((key.to_owned(), value))
+                })
+                .collect::<Result<Map<_, _>>>()
+                .map(Value::Object),
+            FieldDefinition::Function { function, .. } => {
+                let args = self
+                    .children
+                    .iter()
+                    .flatten()
+                    .map(|arg| arg.process(ctx))
+                    .collect::<Result<Vec<_>>>()?;
+                match &self.custom {
+                    Some(Callable::Sync(custom)) => function::call(custom.as_ref(), args),
+                    Some(Callable::Async(_)) => {
+                        Err(FunctionError::FunctionAsync(function.to_string()).into())
+                    }
+                    None => function.process(args),
+                }
+            }
+            _ => self.resolve(ctx),
+        }
+    }
+
+    /// Processes the field awaiting the async functions it calls, which
+    /// `process` rejects.
+    #[async_recursion]
+    pub async fn process_async(&self, ctx: &Context) -> Result<Value> {
+        if !self.asynchronous {
+            return self.process(ctx);
+        }
+
+        match &self.definition {
+            FieldDefinition::Value {
+                value: Value::Array(values),
+            } => {
+                let mut processed = Vec::with_capacity(values.len());
+                for (value, field) in values.iter().zip(&self.children) {
+                    processed.push(match field {
+                        Some(field) => field.process_async(ctx).await?,
+                        None => value.to_owned(),
+                    });
+                }
+                Ok(Value::Array(processed))
+            }
+            FieldDefinition::Value {
+                value: Value::Object(map),
+            } => {
+                let mut processed = Map::with_capacity(map.len());
+                for ((key, value), field) in map.iter().zip(&self.children) {
+                    let value = match field {
+                        Some(field) => field.process_async(ctx).await?,
+                        None => value.to_owned(),
+                    };
+                    processed.insert(key.to_owned(), value);
+                }
+                Ok(Value::Object(processed))
+            }
+            FieldDefinition::Function { function, .. } => {
+                let mut args = Vec::with_capacity(self.children.len());
+                for arg in self.children.iter().flatten() {
+                    args.push(arg.process_async(ctx).await?);
+                }
+                match &self.custom {
+                    Some(Callable::Sync(custom)) => function::call(custom.as_ref(), args),
+                    Some(Callable::Async(custom)) => {
+                        function::call_async(custom.as_ref(), args).await
+                    }
+                    None => function.process(args),
+                }
+            }
+            _ => self.resolve(ctx),
+        }
+    }
+
+    /// Resolves the fields reading from the context and the scalar values.
+    fn resolve(&self, ctx: &Context) -> Result<Value> {
         let value = match &self.definition {
             FieldDefinition::Variable { variable, .. } => ctx.get_variable(variable),
 
@@ -54,39 +210,13 @@ impl Field {
                 output_id, path, ..
             } => ctx.get_output(output_id, path),
 
-            FieldDefinition::Value { value, .. } => match value {
-                Value::Array(values) => {
-                    let values = values
-                        .iter()
-                        .map(|value| {
-                            Self::try_from(value.to_owned())
-                                .map(|field| field.process(ctx))
-                                .unwrap_or(Ok(value.to_owned()))
-                        })
-                        .collect::<Result<Vec<Value>>>()?;
-                    return Ok(Value::Array(values));
-                }
-                Value::Object(map) => {
-                    let map = map
-                        .iter()
-                        .map(|(key, value)| {
-                            Self::try_from(value.to_owned())
-                                .map(|field| field.process(ctx))
-                                .unwrap_or(Ok(value.to_owned()))
-                                .map(|value| (key.to_owned(), value))
-                        })
-                        .collect::<Result<Map<String, Value>>>()?;
-                    return Ok(Value::Object(map));
-                }
-                _ => Some(value.to_owned()),
-            },
-            FieldDefinition::Function { function, args } => {
-                let args = args
-                    .iter()
-                    .map(|arg| Self::from(arg).process(ctx))
-                    .collect::<Result<Vec<Value>>>()?;
-                function.process(args).map(Some)?
-            }
+            FieldDefinition::Item { path } => ctx.get_item(path),
+
+            FieldDefinition::Index => ctx.get_index(),
+
+            FieldDefinition::Value { value } => Some(value.to_owned()),
+
+            FieldDefinition::Function { .. } => None,
         };
 
         match value {
@@ -97,12 +227,46 @@ impl Field {
 
     pub fn dependencies(&self) -> Vec<String> {
         match &self.definition {
-            FieldDefinition::Function { args, .. } => args
+            FieldDefinition::Function { .. } => self
+                .children
                 .iter()
-                .flat_map(|arg| Self::from(arg).dependencies())
+                .flatten()
+                .flat_map(|arg| arg.dependencies())
                 .collect(),
             FieldDefinition::Output { output_id, .. } => vec![output_id.to_owned()],
             _ => vec![],
         }
     }
+
+    fn nested(value: &Value, functions: &FunctionRegistry) -> Option<Field> {
+        functions
+            .scope(|| serde_json::from_value::<FieldDefinition>(value.to_owned()))
+            .ok()
+            .map(|definition| Self::with_functions(&definition, functions))
+    }
+}
+
+impl FieldDefinition {
+    /// Names of the variables read by the field, including the ones read by
+    /// function arguments and by fields nested in array or object values.
+    pub fn variables(&self) -> Vec<String> {
+        match self {
+            FieldDefinition::Variable { variable } => vec![variable.to_owned()],
+            FieldDefinition::Function { args, .. } => {
+                args.iter().flat_map(Self::variables).collect()
+            }
+            FieldDefinition::Value {
+                value: Value::Array(values),
+            } => values.iter().flat_map(Self::nested_variables).collect(),
+            FieldDefinition::Value {
+                value: Value::Object(map),
+            } => map.values().flat_map(Self::nested_variables).collect(),
+            _ => vec![],
+        }
+    }
+
+    fn nested_variables(value: &Value) -> Vec<String> {
+        FunctionRegistry::any_name(|| serde_json::from_value::<Self>(value.to_owned()))
+            .map_or_else(|_| vec![], |definition| definition.variables())
+    }
 }
@@ -0,0 +1,108 @@
+use std::fmt::{self, Display};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{FieldDefinition, Function, Signature};
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldType {
+    String,
+    Number,
+    Boolean,
+    Array,
+    Object,
+    Null,
+    Any,
+}
+
+impl FieldType {
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::String(_) => FieldType::String,
+            Value::Number(_) => FieldType::Number,
+            Value::Bool(_) => FieldType::Boolean,
+            Value::Array(_) => FieldType::Array,
+            Value::Object(_) => FieldType::Object,
+            Value::Null => FieldType::Null,
+        }
+    }
+
+    pub fn is(&self, expected: FieldType) -> bool {
+        *self == FieldType::Any || *self == expected
+    }
+}
+
+impl Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FieldType::String => "string",
+            FieldType::Number => "number",
+            FieldType::Boolean => "boolean",
+            FieldType::Array => "array",
+            FieldType::Object => "object",
+            FieldType::Null => "null",
+            FieldType::Any => "any",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Resolves the static type of data, variables and outputs referenced by
+/// fields. Anything unknown resolves to [`FieldType::Any`].
+pub trait TypeEnv {
+    fn data_type(&self, _path: &str) -> FieldType {
+        FieldType::Any
+    }
+
+    fn variable_type(&self, _variable: &str) -> FieldType {
+        FieldType::Any
+    }
+
+    fn output_type(&self, _output_id: &str, _path: &str) -> FieldType {
+        FieldType::Any
+    }
+
+    fn item_type(&self, _path: &str) -> FieldType {
+        FieldType::Any
+    }
+
+    fn function_signature(&self, _name: &str) -> Option<Signature> {
+        None
+    }
+}
+
+impl FieldDefinition {
+    pub fn infer_type(&self, env: &dyn TypeEnv) -> Result<FieldType> {
+        match self {
+            FieldDefinition::Variable { variable } => Ok(env.variable_type(variable)),
+            FieldDefinition::Data { path } => Ok(env.data_type(path)),
+            FieldDefinition::Output { output_id, path } => Ok(env.output_type(output_id, path)),
+            FieldDefinition::Item { path } => Ok(env.item_type(path)),
+            FieldDefinition::Index => Ok(FieldType::Number),
+            FieldDefinition::Value { value } => Ok(FieldType::of(value)),
+            FieldDefinition::Function { function, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.infer_type(env))
+                    .collect::<Result<Vec<_>>>()?;
+                let inferred = match function {
+                    Function::Custom(name) => match env.function_signature(name) {
+                        Some(signature) => signature.check(&args),
+                        None => function.infer_type(&args),
+                    },
+                    _ => function.infer_type(&args),
+                };
+                inferred.map_err(|err| {
+                    let args = args
+                        .iter()
+                        .map(|arg| format!("`{}`", arg))
+                        .collect::<Vec<_>>();
+                    anyhow!("`{}` called with ({}): {}", function, args.join(", "), err)
+                })
+            }
+        }
+    }
+}
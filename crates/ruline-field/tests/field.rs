@@ -134,3 +134,37 @@ fn test_serialization_invalid_field_type() {
 fn test_serialization_invalid_type() {
     assert_deserialize_error!({ "type": "data", "path": 42 });
 }
+
+#[test]
+fn test_field_display() {
+    let display = |definition| {
+        serde_json::from_value::<ruline_field::FieldDefinition>(definition)
+            .unwrap()
+            .to_string()
+    };
+
+    assert_eq!(
+        display(json!({ "type": "data", "path": "/items/0/unit price" })),
+        "data.items.0[\"unit price\"]"
+    );
+    assert_eq!(
+        display(json!({ "type": "variable", "variable": "score" })),
+        "var.score"
+    );
+    assert_eq!(
+        display(json!({ "type": "output", "output_id": "1", "path": "/fee" })),
+        "output[\"1\"].fee"
+    );
+    assert_eq!(display(json!({ "type": "item", "path": "" })), "item");
+    assert_eq!(
+        display(json!({
+            "type": "function",
+            "function": "add",
+            "args": [
+            { "type": "data", "path": "/a" },
+            { "type": "value", "value": [1, "b"] }
+            ]
+        })),
+        "add(data.a, [1,\"b\"])"
+    );
+}
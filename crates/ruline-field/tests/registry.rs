@@ -0,0 +1,301 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use insta::assert_snapshot;
+use ruline_context::Context;
+use ruline_field::{
+    AsyncFunction, CustomFunction, Field, FieldDefinition, FieldType, FunctionRegistry, Signature,
+    TypeEnv,
+};
+use serde_json::{json, Value};
+
+#[derive(Debug)]
+struct RiskScore;
+
+impl CustomFunction for RiskScore {
+    fn signature(&self) -> Signature {
+        Signature::new(
+            vec![FieldType::String, FieldType::Number],
+            FieldType::Number,
+        )
+    }
+
+    fn call(&self, args: Vec<Value>) -> Result<Value> {
+        let weight = match args[0].as_str() {
+            Some("high") => 3.0,
+            Some("medium") => 2.0,
+            _ => 1.0,
+        };
+        Ok(json!(weight * args[1].as_f64().unwrap_or_default()))
+    }
+}
+
+#[derive(Debug)]
+struct Concat;
+
+impl CustomFunction for Concat {
+    fn signature(&self) -> Signature {
+        Signature::new(vec![], FieldType::String).with_variadic(FieldType::Any)
+    }
+
+    fn call(&self, args: Vec<Value>) -> Result<Value> {
+        Ok(Value::String(
+            args.iter()
+                .map(|arg| match arg {
+                    Value::String(string) => string.to_owned(),
+                    arg => arg.to_string(),
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[derive(Debug)]
+struct Rate;
+
+#[async_trait]
+impl AsyncFunction for Rate {
+    fn signature(&self) -> Signature {
+        Signature::new(vec![FieldType::String], FieldType::Number)
+    }
+
+    async fn call(&self, args: Vec<Value>) -> Result<Value> {
+        tokio::task::yield_now().await;
+
+        Ok(match args[0].as_str() {
+            Some("high") => json!(0.5),
+            _ => json!(0.1),
+        })
+    }
+}
+
+fn registry() -> FunctionRegistry {
+    FunctionRegistry::default()
+        .register("risk_score", RiskScore)
+        .unwrap()
+        .register("concat", Concat)
+        .unwrap()
+        .register_async("rate", Rate)
+        .unwrap()
+}
+
+fn context() -> Context {
+    Context::new(json!({ "level": "high", "amount": 7 }), DashMap::new())
+}
+
+fn deserialize(definition: Value) -> FieldDefinition {
+    registry()
+        .scope(|| serde_json::from_value(definition))
+        .unwrap()
+}
+
+fn field(definition: Value) -> Field {
+    Field::with_functions(&deserialize(definition), &registry())
+}
+
+fn process(definition: Value) -> Result<Value> {
+    field(definition).process(&context())
+}
+
+#[test]
+fn test_registry_call() {
+    let value = process(json!({
+        "type": "function",
+        "function": "risk_score",
+        "args": [
+        { "type": "data", "path": "/level" },
+        {
+            "type": "function",
+            "function": "add",
+            "args": [
+            { "type": "data", "path": "/amount" },
+            { "type": "value", "value": 1 }
+            ]
+        }
+        ]
+    }))
+    .unwrap();
+    assert_eq!(value, json!(24.0));
+
+    let value = process(json!({
+        "type": "function",
+        "function": "concat",
+        "args": [
+        { "type": "data", "path": "/level" },
+        { "type": "value", "value": "-" },
+        { "type": "data", "path": "/amount" }
+        ]
+    }))
+    .unwrap();
+    assert_eq!(value, json!("high-7"));
+}
+
+#[test]
+fn test_registry_nested_value() {
+    let value = process(json!({
+        "type": "value",
+        "value": [{
+            "type": "function",
+            "function": "concat",
+            "args": [{ "type": "data", "path": "/level" }]
+        }]
+    }))
+    .unwrap();
+    assert_eq!(value, json!(["high"]));
+}
+
+#[test]
+fn test_registry_function_not_found() {
+    let definition = deserialize(json!({
+        "type": "function",
+        "function": "risk_score",
+        "args": []
+    }));
+    let context = Context::new(json!({}), DashMap::new());
+    let result = Field::from(&definition).process(&context);
+    assert_snapshot!(result.unwrap_err().to_string());
+}
+
+#[test]
+fn test_registry_scope() {
+    let definition = json!({ "type": "function", "function": "risk_score", "args": [] });
+    let deserialize = || serde_json::from_value::<FieldDefinition>(definition.to_owned());
+
+    assert!(registry().scope(deserialize).is_ok());
+    assert_snapshot!(deserialize().unwrap_err().to_string());
+}
+
+#[test]
+fn test_registry_arguments_invalid() {
+    let errors = [
+        json!([{ "type": "data", "path": "/level" }]),
+        json!([
+            { "type": "data", "path": "/amount" },
+            { "type": "data", "path": "/amount" }
+        ]),
+    ]
+    .into_iter()
+    .map(|args| {
+        process(json!({ "type": "function", "function": "risk_score", "args": args }))
+            .unwrap_err()
+            .to_string()
+    })
+    .collect::<Vec<_>>();
+
+    assert_snapshot!(errors.join("\n"));
+}
+
+#[test]
+fn test_registry_register_invalid() {
+    let errors = ["add", "risk-score", "1st", ""]
+        .into_iter()
+        .map(|name| {
+            FunctionRegistry::default()
+                .register(name, RiskScore)
+                .unwrap_err()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+
+    assert_snapshot!(errors.join("\n"));
+}
+
+#[test]
+fn test_registry_names() {
+    let registry = registry();
+    let mut names = registry.names().collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["concat", "rate", "risk_score"]);
+    assert!(!registry.contains("add"));
+}
+
+fn rated() -> Field {
+    field(json!({
+        "type": "value",
+        "value": {
+            "rate": {
+                "type": "function",
+                "function": "concat",
+                "args": [{
+                    "type": "function",
+                    "function": "rate",
+                    "args": [{ "type": "data", "path": "/level" }]
+                }]
+            },
+            "level": { "type": "data", "path": "/level" }
+        }
+    }))
+}
+
+#[tokio::test]
+async fn test_registry_call_async() {
+    let field = rated();
+    assert!(field.is_async());
+
+    let value = field.process_async(&context()).await.unwrap();
+    assert_eq!(value, json!({ "rate": "0.5", "level": "high" }));
+
+    let field = self::field(json!({ "type": "data", "path": "/amount" }));
+    assert!(!field.is_async());
+    assert_eq!(field.process_async(&context()).await.unwrap(), json!(7));
+}
+
+#[test]
+fn test_registry_call_async_from_sync() {
+    let err = rated().process(&context()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Function `rate` is async and can only be called from async processing"
+    );
+}
+
+struct Env(FunctionRegistry);
+
+impl TypeEnv for Env {
+    fn data_type(&self, path: &str) -> FieldType {
+        match path {
+            "/level" => FieldType::String,
+            "/amount" => FieldType::Number,
+            _ => FieldType::Any,
+        }
+    }
+
+    fn function_signature(&self, name: &str) -> Option<Signature> {
+        self.0.signature(name)
+    }
+}
+
+fn infer(definition: Value) -> Result<FieldType> {
+    deserialize(definition).infer_type(&Env(registry()))
+}
+
+#[test]
+fn test_registry_infer_type() {
+    assert_eq!(
+        infer(json!({
+            "type": "function",
+            "function": "risk_score",
+            "args": [
+            { "type": "data", "path": "/level" },
+            { "type": "variable", "variable": "amount" }
+            ]
+        }))
+        .unwrap(),
+        FieldType::Number
+    );
+
+    assert_snapshot!(infer(json!({
+        "type": "function",
+        "function": "risk_score",
+        "args": [
+        { "type": "data", "path": "/amount" },
+        { "type": "data", "path": "/amount" }
+        ]
+    }))
+    .unwrap_err()
+    .to_string());
+}
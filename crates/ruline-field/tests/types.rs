@@ -0,0 +1,83 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use insta::assert_snapshot;
+use ruline_field::{FieldDefinition, FieldType, TypeEnv};
+use serde_json::{json, Value};
+
+struct Env;
+
+impl TypeEnv for Env {
+    fn data_type(&self, path: &str) -> FieldType {
+        match path {
+            "/name" => FieldType::String,
+            "/age" => FieldType::Number,
+            _ => FieldType::Any,
+        }
+    }
+}
+
+fn infer(definition: Value) -> anyhow::Result<FieldType> {
+    let definition: FieldDefinition = serde_json::from_value(definition).unwrap();
+    definition.infer_type(&Env)
+}
+
+#[test]
+fn test_infer_type() {
+    assert_eq!(
+        infer(json!({ "type": "value", "value": "John" })).unwrap(),
+        FieldType::String
+    );
+    assert_eq!(
+        infer(json!({ "type": "data", "path": "/unknown" })).unwrap(),
+        FieldType::Any
+    );
+    assert_eq!(
+        infer(json!({
+            "type": "function",
+            "function": "join",
+            "args": [
+            { "type": "value", "value": " " },
+            { "type": "data", "path": "/name" },
+            { "type": "value", "value": ["a", "b"] }
+            ]
+        }))
+        .unwrap(),
+        FieldType::String
+    );
+    assert_eq!(
+        infer(json!({
+            "type": "function",
+            "function": "mod",
+            "args": [
+            { "type": "data", "path": "/age" },
+            { "type": "variable", "variable": "divisor" }
+            ]
+        }))
+        .unwrap(),
+        FieldType::Number
+    );
+}
+
+#[test]
+fn test_infer_type_argument_invalid() {
+    assert_snapshot!(infer(json!({
+        "type": "function",
+        "function": "upper",
+        "args": [{ "type": "data", "path": "/age" }]
+    }))
+    .unwrap_err()
+    .to_string());
+}
+
+#[test]
+fn test_infer_type_arity_invalid() {
+    assert_snapshot!(infer(json!({
+        "type": "function",
+        "function": "pow",
+        "args": [{ "type": "value", "value": 2 }]
+    }))
+    .unwrap_err()
+    .to_string());
+}
@@ -2,9 +2,9 @@ use std::collections::HashMap;
 
 use anyhow::Result;
 use ruline_context::Context;
-use ruline_field::{Field, FieldDefinition};
+use ruline_field::{Field, FieldDefinition, FunctionRegistry};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 mod error;
 pub use error::OutputError;
@@ -12,19 +12,46 @@ pub use error::OutputError;
 #[derive(Debug, Deserialize, Serialize)]
 pub struct OutputDefinition(HashMap<String, FieldDefinition>);
 
+impl OutputDefinition {
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &FieldDefinition)> {
+        self.0.iter().map(|(key, field)| (key.as_str(), field))
+    }
+}
+
 #[derive(Debug)]
 pub struct Output {
     pub definition: OutputDefinition,
+    fields: Vec<(String, Field)>,
 }
 
 impl Output {
+    /// Rebuilds the fields resolving the custom functions they call in
+    /// `functions`.
+    pub fn with_functions(mut self, functions: &FunctionRegistry) -> Self {
+        self.fields = self
+            .definition
+            .fields()
+            .map(|(key, value)| (key.to_owned(), Field::with_functions(value, functions)))
+            .collect();
+        self
+    }
+
     pub fn process(&self, ctx: &Context) -> Result<Value> {
-        let mut output = HashMap::new();
+        let mut output = Map::with_capacity(self.fields.len());
 
-        for (key, value) in &self.definition.0 {
-            output.insert(key.to_owned(), Field::from(value).process(ctx)?);
+        for (key, field) in &self.fields {
+            output.insert(key.to_owned(), field.process(ctx)?);
         }
-        Ok(serde_json::to_value(output)?)
+        Ok(Value::Object(output))
+    }
+
+    pub async fn process_async(&self, ctx: &Context) -> Result<Value> {
+        let mut output = Map::with_capacity(self.fields.len());
+
+        for (key, field) in &self.fields {
+            output.insert(key.to_owned(), field.process_async(ctx).await?);
+        }
+        Ok(Value::Object(output))
     }
 }
 
@@ -32,7 +59,13 @@ impl TryFrom<OutputDefinition> for Output {
     type Error = OutputError;
 
     fn try_from(definition: OutputDefinition) -> Result<Self, Self::Error> {
-        Ok(Output { definition })
+        let fields = definition
+            .0
+            .iter()
+            .map(|(key, value)| (key.to_owned(), Field::from(value)))
+            .collect();
+
+        Ok(Output { definition, fields })
     }
 }
 
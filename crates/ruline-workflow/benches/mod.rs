@@ -0,0 +1,125 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use ruline_workflow::Workflow;
+use serde_json::{json, Map, Value};
+
+const TIERS: usize = 100;
+
+fn definition() -> Value {
+    let mut definition = Map::new();
+
+    for tier in 0..TIERS {
+        let condition_id = format!("{}", tier * 3 + 1);
+        let action_id = format!("{}", tier * 3 + 2);
+        let fallback_id = format!("{}", tier * 3 + 3);
+
+        let mut results = vec![json!(action_id)];
+        if tier + 1 < TIERS {
+            results.push(json!(format!("{}", (tier + 1) * 3 + 1)));
+        }
+
+        definition.insert(
+            condition_id,
+            json!({
+                "type": "condition",
+                "name": format!("check_tier_{}", tier),
+                "definition": {
+                    "type": "binary",
+                    "fallbacks": [ fallback_id ],
+                    "results": results,
+                    "expression": {
+                        "id": "100",
+                        "type": "logical",
+                        "operator": "and",
+                        "expressions": [
+                        {
+                            "id": "101",
+                            "type": "comparison",
+                            "operator": "greater_than",
+                            "operands": [
+                            {
+                                "type": "function",
+                                "function": "add",
+                                "args": [
+                                { "type": "data", "path": "/amount" },
+                                { "type": "value", "value": tier }
+                                ]
+                            },
+                            { "type": "value", "value": 0 }
+                            ]
+                        },
+                        {
+                            "id": "102",
+                            "type": "comparison",
+                            "operator": "contains",
+                            "operands": [
+                            { "type": "data", "path": "/country" },
+                            { "type": "value", "value": [ "AR", "UY", "BR" ] }
+                            ]
+                        }
+                        ]
+                    }
+                }
+            }),
+        );
+
+        definition.insert(
+            action_id,
+            json!({
+                "type": "action",
+                "name": format!("score_tier_{}", tier),
+                "definition": {
+                    "type": "set_variable",
+                    "variable": format!("score_{}", tier),
+                    "value": {
+                        "type": "function",
+                        "function": "mul",
+                        "args": [
+                        { "type": "data", "path": "/amount" },
+                        { "type": "value", "value": tier }
+                        ]
+                    }
+                }
+            }),
+        );
+
+        definition.insert(
+            fallback_id,
+            json!({
+                "type": "action",
+                "name": format!("reject_tier_{}", tier),
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "rejected",
+                    "value": { "type": "value", "value": tier }
+                }
+            }),
+        );
+    }
+
+    Value::Object(definition)
+}
+
+pub fn benchmark(c: &mut Criterion) {
+    let workflow = Workflow::builder()
+        .with_definition(definition())
+        .with_output(json!({
+            "score": {
+                "type": "variable",
+                "variable": format!("score_{}", TIERS - 1)
+            }
+        }))
+        .build()
+        .unwrap();
+
+    let data = json!({
+        "amount": 42,
+        "country": "AR",
+    });
+
+    c.bench_function("process_workflow", |b| {
+        b.iter(|| workflow.process(data.to_owned()).unwrap())
+    });
+}
+
+criterion_group!(benches, benchmark);
+criterion_main!(benches);
@@ -0,0 +1,65 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{mpsc, Mutex},
+    thread,
+};
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::Workflow;
+
+impl Workflow {
+    /// Processes every input on a pool of worker threads sharing this
+    /// workflow, returning one result per input in the same order.
+    pub fn process_batch(&self, inputs: Vec<Value>) -> Vec<Result<Value>> {
+        let workers = thread::available_parallelism()
+            .map(NonZeroUsize::get)
+            .unwrap_or(1);
+        self.process_batch_with_workers(inputs, workers)
+    }
+
+    pub fn process_batch_with_workers(
+        &self,
+        inputs: Vec<Value>,
+        workers: usize,
+    ) -> Vec<Result<Value>> {
+        let len = inputs.len();
+        let workers = workers.clamp(1, len.max(1));
+
+        let (job_sender, job_receiver) = mpsc::channel();
+        let (result_sender, result_receiver) = mpsc::channel();
+        let job_receiver = Mutex::new(job_receiver);
+
+        for job in inputs.into_iter().enumerate() {
+            job_sender.send(job).unwrap();
+        }
+        drop(job_sender);
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                let job_receiver = &job_receiver;
+                let result_sender = result_sender.clone();
+
+                scope.spawn(move || loop {
+                    let job = job_receiver.lock().unwrap().recv();
+                    let Ok((index, data)) = job else {
+                        break;
+                    };
+
+                    if result_sender.send((index, self.process(data))).is_err() {
+                        break;
+                    }
+                });
+            }
+        });
+        drop(result_sender);
+
+        let mut results = (0..len).map(|_| None).collect::<Vec<_>>();
+        for (index, result) in result_receiver {
+            results[index] = Some(result);
+        }
+
+        results.into_iter().flatten().collect()
+    }
+}
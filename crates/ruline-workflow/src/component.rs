@@ -0,0 +1,49 @@
+use std::fmt::Debug;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use ruline_action::Action;
+use ruline_condition::Condition;
+use ruline_context::Context;
+
+/// A component that can be processed without blocking the runtime, returning
+/// the dependants selected to run next.
+#[async_trait]
+pub trait AsyncComponent: Debug + Send + Sync {
+    fn dependencies(&self) -> Vec<String>;
+
+    fn dependants(&self) -> Vec<String>;
+
+    async fn process(&self, ctx: &Context) -> Result<Vec<String>>;
+}
+
+#[async_trait]
+impl AsyncComponent for Condition {
+    fn dependencies(&self) -> Vec<String> {
+        Condition::dependencies(self)
+    }
+
+    fn dependants(&self) -> Vec<String> {
+        Condition::dependants(self)
+    }
+
+    async fn process(&self, ctx: &Context) -> Result<Vec<String>> {
+        self.evaluate_async(ctx).await
+    }
+}
+
+#[async_trait]
+impl AsyncComponent for Action {
+    fn dependencies(&self) -> Vec<String> {
+        Action::dependencies(self)
+    }
+
+    fn dependants(&self) -> Vec<String> {
+        vec![]
+    }
+
+    async fn process(&self, ctx: &Context) -> Result<Vec<String>> {
+        self.process_async(ctx).await?;
+        Ok(vec![])
+    }
+}
@@ -1,8 +1,12 @@
 use ruline_action::ActionError;
 use ruline_condition::ConditionError;
 use ruline_output::OutputError;
+use std::time::Duration;
+
 use thiserror::Error;
 
+use crate::{SchemaViolation, TypeMismatch, VariableType};
+
 #[derive(Debug, Error)]
 pub enum WorkflowError {
     #[error("Dependency `{dependency}` for component `{component_id}` not found")]
@@ -16,9 +20,123 @@ pub enum WorkflowError {
         dependant: String,
     },
 
+    #[error("Component `{0}` is defined more than once")]
+    DuplicateComponent(String),
+
+    #[error("Initial value of variable `{variable}` is not of type `{expected}`")]
+    VariableTypeMismatch {
+        variable: String,
+        expected: VariableType,
+    },
+
+    #[error("Input is invalid: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    InputInvalid(Vec<SchemaViolation>),
+
+    #[error("Type check failed: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    TypeMismatch(Vec<TypeMismatch>),
+
+    #[error("Component `{0}` calls a workflow but no resolver is configured")]
+    ResolverMissing(String),
+    #[error("Component `{component_id}` calls workflow `{workflow_id}` recursively")]
+    WorkflowRecursion {
+        component_id: String,
+        workflow_id: String,
+    },
+    #[error("Workflow `{workflow_id}` exceeds the maximum nesting depth of {max_depth}")]
+    WorkflowDepthExceeded {
+        workflow_id: String,
+        max_depth: usize,
+    },
+
+    #[error("Workflow `{0}` has no active version")]
+    WorkflowNotActive(String),
+    #[error("Version `{version}` of workflow `{workflow_id}` is active and cannot be evicted")]
+    WorkflowActive {
+        workflow_id: String,
+        version: String,
+    },
+    #[error("Workflow registry was dropped")]
+    RegistryDropped,
+
+    #[error("Items of component `{0}` must be an array")]
+    ForEachItemsInvalid(String),
+
+    #[error("Decision table `{component_id}` is invalid: {message}")]
+    DecisionTableInvalid {
+        component_id: String,
+        message: String,
+    },
+    #[error("Rules {} of decision table `{component_id}` overlap for input {input}", format!("{} and {}", .rules.0, .rules.1))]
+    DecisionTableOverlap {
+        component_id: String,
+        rules: (usize, usize),
+        input: serde_json::Value,
+    },
+    #[error("No rule of decision table `{component_id}` matches input {input}")]
+    DecisionTableIncomplete {
+        component_id: String,
+        input: serde_json::Value,
+    },
+    #[error("Rules {} of decision table `{component_id}` all match", .rules.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    DecisionTableNotUnique {
+        component_id: String,
+        rules: Vec<usize>,
+    },
+
+    #[error("Scorecard `{component_id}` is invalid: {message}")]
+    ScorecardInvalid {
+        component_id: String,
+        message: String,
+    },
+    #[error(
+        "No bin of characteristic `{characteristic}` in scorecard `{component_id}` matches {value}"
+    )]
+    ScorecardBinMissing {
+        component_id: String,
+        characteristic: String,
+        value: serde_json::Value,
+    },
+
+    #[error("Component `{0}` is a condition and cannot fall back to a default value")]
+    ErrorPolicyUnsupported(String),
+
+    #[error("Processed more than {0} components")]
+    ComponentLimitExceeded(usize),
+    #[error("Function nesting depth {depth} in `{location}` exceeds the limit of {limit}")]
+    FunctionDepthExceeded {
+        location: String,
+        depth: usize,
+        limit: usize,
+    },
+    #[error("Array of {length} items produced by `{component_id}` exceeds the limit of {limit}")]
+    ArrayTooLarge {
+        component_id: String,
+        length: usize,
+        limit: usize,
+    },
+    #[error("Input of {size} bytes exceeds the limit of {limit}")]
+    InputTooLarge { size: usize, limit: usize },
+    #[error("Output of {size} bytes exceeds the limit of {limit}")]
+    OutputTooLarge { size: usize, limit: usize },
+    #[error("Processing exceeded the deadline of {0:?}")]
+    DeadlineExceeded(Duration),
+
+    #[error(
+        "Invalid {format}{}: {message}",
+        location.map(|(line, column)| format!(" at line {}, column {}", line, column)).unwrap_or_default()
+    )]
+    Syntax {
+        format: &'static str,
+        location: Option<(usize, usize)>,
+        message: String,
+    },
+
     #[error("Cycle detected")]
     CycleDetected,
 
+    #[error("Component `{0}` can only be processed asynchronously")]
+    AsyncComponent(String),
+
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
 
@@ -0,0 +1,192 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use ruline_action::ActionDefinition;
+use ruline_condition::{ConditionDefinition, Diagram, EdgeStyle, Shape};
+
+use crate::{plan::Step, Component, Workflow};
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Chunk<'a> {
+    /// Digits without leading zeros, ordered by length first.
+    Number(usize, &'a str),
+    Text(&'a str),
+}
+
+fn chunks(id: &str) -> impl Iterator<Item = Chunk<'_>> {
+    let mut rest = id;
+    std::iter::from_fn(move || {
+        let digits = rest.chars().next()?.is_ascii_digit();
+        let end = rest
+            .find(|char: char| char.is_ascii_digit() != digits)
+            .unwrap_or(rest.len());
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+
+        Some(match digits {
+            true => {
+                let number = chunk.trim_start_matches('0');
+                Chunk::Number(number.len(), number)
+            }
+            false => Chunk::Text(chunk),
+        })
+    })
+}
+
+/// Compares ids by the value of the numbers in them, so `2` comes before `10`.
+fn natural(left: &str, right: &str) -> Ordering {
+    chunks(left)
+        .cmp(chunks(right))
+        .then_with(|| left.cmp(right))
+}
+
+fn describe(step: &Step) -> (String, Shape) {
+    let (detail, shape) = match &step.component {
+        Component::Condition(condition) => match condition.definition() {
+            ConditionDefinition::Binary { expression, .. } => {
+                (expression.to_string(), Shape::Diamond)
+            }
+            ConditionDefinition::Decision { expressions, .. } => (
+                expressions
+                    .iter()
+                    .map(|expression| format!("{}: {}", expression.get_id(), expression))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Shape::Diamond,
+            ),
+        },
+        Component::Action(action) => match &action.definition {
+            ActionDefinition::SetVariable { variable, value } => {
+                (format!("var.{} = {}", variable, value), Shape::Box)
+            }
+        },
+        Component::Workflow(workflow) => (
+            format!("workflow {}", workflow.definition.workflow_id),
+            Shape::Rounded,
+        ),
+        Component::ForEach(for_each) => (
+            format!("for each {}", for_each.definition.items),
+            Shape::Rounded,
+        ),
+        Component::DecisionTable(table) => (
+            format!(
+                "decision table of {}",
+                table
+                    .definition
+                    .inputs
+                    .iter()
+                    .map(|input| input.field.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Shape::Box,
+        ),
+        Component::Scorecard(scorecard) => (
+            format!(
+                "scorecard of {}",
+                scorecard
+                    .definition
+                    .characteristics
+                    .iter()
+                    .map(|characteristic| characteristic.field.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Shape::Box,
+        ),
+        Component::Async(_) => (String::new(), Shape::Rounded),
+    };
+
+    let label = match detail.is_empty() {
+        true => format!("{}: {}", step.id, step.name),
+        false => format!("{}: {}\n{}", step.id, step.name, detail),
+    };
+    (label, shape)
+}
+
+/// Why `from` leads to `dependant`: the condition branches selecting it, the
+/// error route, or `None` when the dependant only reads an output of `from`.
+fn edge_label(from: &Step, dependant: &str) -> Option<String> {
+    let mut labels = Vec::new();
+
+    if let Component::Condition(condition) = &from.component {
+        match condition.definition() {
+            ConditionDefinition::Binary {
+                results, fallbacks, ..
+            } => {
+                if results.iter().any(|id| id == dependant) {
+                    labels.push("results".to_owned());
+                }
+                if fallbacks.iter().any(|id| id == dependant) {
+                    labels.push("fallbacks".to_owned());
+                }
+            }
+            ConditionDefinition::Decision {
+                results, fallbacks, ..
+            } => {
+                let mut matched = results
+                    .iter()
+                    .filter(|(_, dependants)| dependants.iter().any(|id| id == dependant))
+                    .map(|(expression_id, _)| format!("results {}", expression_id))
+                    .collect::<Vec<_>>();
+                matched.sort();
+                labels.extend(matched);
+                if fallbacks.iter().any(|id| id == dependant) {
+                    labels.push("fallbacks".to_owned());
+                }
+            }
+        }
+    }
+
+    if let Some(policy) = &from.policy {
+        if policy.dependants().iter().any(|id| id == dependant) {
+            labels.push("on error".to_owned());
+        }
+    }
+
+    (!labels.is_empty()).then(|| labels.join(", "))
+}
+
+impl Workflow {
+    /// Diagram of the components in natural order of their ids. Branch and error edges are
+    /// labelled and solid, edges from outputs to their readers are dashed.
+    pub fn diagram(&self) -> Diagram {
+        let mut steps = self.plan.steps().collect::<Vec<_>>();
+        steps.sort_by(|left, right| natural(&left.id, &right.id));
+
+        let mut diagram = Diagram::default();
+        let nodes = steps
+            .iter()
+            .map(|step| {
+                let (label, shape) = describe(step);
+                (step.id.as_str(), diagram.node(label, shape))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut edges = self.edges().collect::<Vec<_>>();
+        edges.sort_by(|(left_from, left_to), (right_from, right_to)| {
+            natural(left_from, right_from).then_with(|| natural(left_to, right_to))
+        });
+        edges.dedup();
+        for (from, to) in edges {
+            let Some(step) = steps.iter().find(|step| step.id == from) else {
+                continue;
+            };
+            let label = edge_label(step, to);
+            let style = match label {
+                Some(_) => EdgeStyle::Solid,
+                None => EdgeStyle::Dashed,
+            };
+            diagram.edge(nodes[from], nodes[to], label, style);
+        }
+
+        diagram
+    }
+
+    pub fn to_dot(&self) -> String {
+        self.diagram().to_dot("workflow")
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        self.diagram().to_mermaid()
+    }
+}
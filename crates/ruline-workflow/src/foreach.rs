@@ -0,0 +1,97 @@
+use anyhow::Result;
+use ruline_context::Context;
+use ruline_field::{Field, FieldDefinition, FunctionRegistry};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{limits::Budget, Workflow, WorkflowError};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ForEachDefinition {
+    pub items: FieldDefinition,
+    pub components: Value,
+    pub output: Value,
+}
+
+/// Runs a nested workflow once per element of an array, with the element and
+/// its index readable through `item` and `index` fields, and publishes the
+/// per-item outputs as an array under the component id.
+#[derive(Debug)]
+pub struct ForEach {
+    pub id: String,
+    pub definition: ForEachDefinition,
+    pub workflow: Workflow,
+    items: Field,
+}
+
+impl ForEach {
+    pub fn new(
+        id: &str,
+        definition: ForEachDefinition,
+        workflow: Workflow,
+        functions: &FunctionRegistry,
+    ) -> Self {
+        let items = Field::with_functions(&definition.items, functions);
+
+        Self {
+            id: id.to_owned(),
+            definition,
+            workflow,
+            items,
+        }
+    }
+
+    /// Processes every item charging the components to `budget`, which the
+    /// workflow running the component passes down so its limits hold.
+    pub(crate) fn run(&self, ctx: &Context, budget: &Budget) -> Result<Value> {
+        let results = self
+            .items(ctx)?
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let scope = Self::scope(ctx, index, item);
+                self.workflow.execute(&scope, budget, None)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let output = Value::Array(results);
+        ctx.set_output(self.id.to_owned(), output.to_owned());
+        Ok(output)
+    }
+
+    pub(crate) async fn run_async(&self, ctx: &Context, budget: &Budget) -> Result<Value> {
+        let mut results = Vec::new();
+        for (index, item) in self.items_async(ctx).await?.into_iter().enumerate() {
+            let scope = Self::scope(ctx, index, item);
+            results.push(self.workflow.execute_async(&scope, budget).await?);
+        }
+
+        let output = Value::Array(results);
+        ctx.set_output(self.id.to_owned(), output.to_owned());
+        Ok(output)
+    }
+
+    pub fn dependencies(&self) -> Vec<String> {
+        self.items.dependencies()
+    }
+
+    fn items(&self, ctx: &Context) -> Result<Vec<Value>> {
+        match self.items.process(ctx)? {
+            Value::Array(items) => Ok(items),
+            _ => Err(WorkflowError::ForEachItemsInvalid(self.id.to_owned()).into()),
+        }
+    }
+
+    async fn items_async(&self, ctx: &Context) -> Result<Vec<Value>> {
+        match self.items.process_async(ctx).await? {
+            Value::Array(items) => Ok(items),
+            _ => Err(WorkflowError::ForEachItemsInvalid(self.id.to_owned()).into()),
+        }
+    }
+
+    /// Each iteration sees the input data and a copy of the variables, so
+    /// items cannot leak state into one another or into the outer workflow.
+    fn scope(ctx: &Context, index: usize, item: Value) -> Context {
+        Context::new(ctx.data.to_owned(), ctx.variables.to_owned()).with_item(index, item)
+    }
+}
@@ -0,0 +1,46 @@
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::WorkflowError;
+
+#[cfg(feature = "yaml")]
+pub fn from_yaml<T: DeserializeOwned + Serialize>(source: &str) -> Result<Value, WorkflowError> {
+    let parsed: T = serde_norway::from_str(source).map_err(|err| {
+        let location = err
+            .location()
+            .map(|location| (location.line(), location.column()));
+        let mut message = err.to_string();
+        if let Some((line, column)) = location {
+            let position = format!(" at line {} column {}", line, column);
+            message = message.replacen(&position, "", 1);
+        }
+
+        WorkflowError::Syntax {
+            format: "YAML",
+            location,
+            message,
+        }
+    })?;
+
+    serde_json::to_value(parsed).map_err(WorkflowError::Serde)
+}
+
+#[cfg(feature = "toml")]
+pub fn from_toml<T: DeserializeOwned + Serialize>(source: &str) -> Result<Value, WorkflowError> {
+    let parsed: T = toml::from_str(source).map_err(|err| WorkflowError::Syntax {
+        format: "TOML",
+        location: err.span().map(|span| line_column(source, span.start)),
+        message: err.message().to_owned(),
+    })?;
+
+    serde_json::to_value(parsed).map_err(WorkflowError::Serde)
+}
+
+/// One-based line and column, counted in characters, of a byte offset.
+#[cfg(feature = "toml")]
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// How a component with several parents decides whether to run once every
+/// parent is resolved. A parent selects the component when it ran and, if it
+/// branches, chose it; a parent that was skipped neither selects nor rejects.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JoinMode {
+    /// Run when every parent that ran selected the component.
+    #[default]
+    All,
+    /// Run when at least one parent selected the component, even if others
+    /// left it out.
+    Any,
+}
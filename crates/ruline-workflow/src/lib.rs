@@ -1,17 +1,66 @@
+mod batch;
+mod component;
 mod error;
+mod export;
+mod foreach;
+#[cfg(any(feature = "yaml", feature = "toml"))]
+mod format;
+mod join;
+mod limits;
+mod lint;
+mod parallel;
+mod plan;
+mod policy;
+mod registry;
+mod schedule;
+mod schema;
+mod scorecard;
+mod spec;
+mod subworkflow;
+mod table;
+mod trace;
+mod typecheck;
+mod variable;
 
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Instant,
+};
 
 use anyhow::Result;
+use async_recursion::async_recursion;
+pub use component::AsyncComponent;
 use dashmap::DashMap;
-use error::WorkflowError;
-use petgraph::{csr::IndexType, graph::DiGraph, visit::Bfs, Direction};
+pub use error::WorkflowError;
+pub use foreach::{ForEach, ForEachDefinition};
+pub use join::JoinMode;
+use limits::Budget;
+pub use limits::Limits;
+pub use lint::{LintKind, LintWarning};
+use petgraph::{graph::DiGraph, Direction};
+use plan::Plan;
+pub use policy::ErrorPolicy;
+pub use registry::{WorkflowRegistry, WorkflowSource};
 use ruline_action::{Action, ActionDefinition};
-use ruline_condition::{Condition, ConditionDefinition};
+use ruline_condition::{Condition, ConditionDefinition, OperatorRegistry};
 use ruline_context::Context;
+use ruline_field::FunctionRegistry;
 use ruline_output::Output;
+use schedule::Scheduler;
+pub use schema::{Schema, SchemaType, SchemaViolation, ViolationKind};
+pub use scorecard::{Characteristic, Scorecard, ScorecardBin, ScorecardDefinition};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+pub use spec::WorkflowSpec;
+pub use subworkflow::{SubWorkflow, SubWorkflowDefinition, WorkflowResolver};
+pub use table::{
+    DecisionTable, DecisionTableDefinition, HitPolicy, TableInput, TableOutput, TableRule,
+    TableTest,
+};
+pub use trace::{Trace, TraceOutcome, TraceStep};
+pub use typecheck::TypeMismatch;
+pub use variable::{VariableDefinition, VariableType};
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
@@ -19,24 +68,108 @@ pub enum ComponentDefinition {
     Condition {
         name: String,
         definition: ConditionDefinition,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        on_error: Option<ErrorPolicy>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        join: Option<JoinMode>,
     },
     Action {
         name: String,
         definition: ActionDefinition,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        on_error: Option<ErrorPolicy>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        join: Option<JoinMode>,
+    },
+    Workflow {
+        name: String,
+        definition: SubWorkflowDefinition,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        on_error: Option<ErrorPolicy>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        join: Option<JoinMode>,
+    },
+    ForEach {
+        name: String,
+        definition: ForEachDefinition,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        on_error: Option<ErrorPolicy>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        join: Option<JoinMode>,
+    },
+    DecisionTable {
+        name: String,
+        definition: DecisionTableDefinition,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        on_error: Option<ErrorPolicy>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        join: Option<JoinMode>,
+    },
+    Scorecard {
+        name: String,
+        definition: ScorecardDefinition,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        on_error: Option<ErrorPolicy>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        join: Option<JoinMode>,
     },
 }
 
+/// A component of a workflow. Each run publishes an output under the
+/// component id, readable by later components through `output` fields:
+///
+/// - condition: `{ "branch": "results" | "fallbacks", "matched": [<expression id>] }`
+/// - action: `{ "variable": <name>, "value": <value written> }`
+/// - workflow: the output of the called workflow
+/// - for each: an array with the output of each iteration
+/// - decision table: the outputs picked by the hit policy
+/// - scorecard: `{ "score": <number>, "points": { <characteristic>: <number> }, "reason_codes": [<code>] }`
+///
+/// A component whose error is handled by its policy publishes
+/// `{ "error": { "message": <message> } }` instead or, with a `default`
+/// policy on anything but an action, the default value.
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum Component {
     Condition(Condition),
     Action(Action),
+    Workflow(SubWorkflow),
+    ForEach(ForEach),
+    DecisionTable(DecisionTable),
+    Scorecard(Scorecard),
+    Async(Box<dyn AsyncComponent>),
+}
+
+impl Component {
+    pub fn dependencies(&self) -> Vec<String> {
+        match self {
+            Component::Condition(condition) => condition.dependencies(),
+            Component::Action(action) => action.dependencies(),
+            Component::Workflow(workflow) => workflow.dependencies(),
+            Component::ForEach(for_each) => for_each.dependencies(),
+            Component::DecisionTable(table) => table.dependencies(),
+            Component::Scorecard(scorecard) => scorecard.dependencies(),
+            Component::Async(component) => component.dependencies(),
+        }
+    }
+
+    pub fn dependants(&self) -> Vec<String> {
+        match self {
+            Component::Condition(condition) => condition.dependants(),
+            Component::Async(component) => component.dependants(),
+            _ => vec![],
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Workflow {
-    components: HashMap<String, Component>,
-    variables: DashMap<String, Value>,
+    plan: Plan,
+    max_depth: usize,
+    limits: Limits,
+    variables: HashMap<String, VariableDefinition>,
+    schema: Option<Schema>,
+    functions: FunctionRegistry,
     output: Output,
     graph: DiGraph<String, ()>,
 }
@@ -47,47 +180,249 @@ impl Workflow {
     }
 
     pub fn process(&self, data: Value) -> Result<Value> {
-        let context = Context::new(data, self.variables.to_owned());
-        let mut bfs = Bfs::new(&self.graph, self.graph.node_indices().next().unwrap());
+        self.run(data, None)
+    }
 
-        while let Some(node) = bfs.next(&self.graph) {
-            if self.graph[node].eq("0") {
-                continue;
-            }
+    pub fn process_with_trace(&self, data: Value) -> Result<(Value, Trace)> {
+        let mut trace = Trace::default();
+        let output = self.run(data, Some(&mut trace))?;
+        Ok((output, trace))
+    }
 
-            match self.components.get(&self.graph[node]).unwrap() {
-                Component::Condition(condition) => {
-                    let result = condition.evaluate(&context)?;
-                    let dependants_skipped = condition
-                        .dependants()
-                        .iter()
-                        .filter_map(|d| self.graph.node_indices().find(|n| self.graph[*n] == *d))
-                        .filter(|&n| !result.contains(&self.graph[n]))
-                        .collect::<Vec<_>>();
-
-                    for skipped in dependants_skipped {
-                        bfs.stack.retain(|n| n.index() != skipped.index());
-                    }
-                }
-                Component::Action(a) => {
-                    a.process(&context)?;
+    fn run(&self, data: Value, trace: Option<&mut Trace>) -> Result<Value> {
+        let context = self.context(data)?;
+        self.execute(&context, &Budget::new(self), trace)
+    }
+
+    /// Processes `data` as a run nested in another one, charged to its budget.
+    fn process_nested(&self, data: Value, budget: &Budget) -> Result<Value> {
+        let context = self.context(data)?;
+        self.execute(&context, budget, None)
+    }
+
+    fn execute(
+        &self,
+        context: &Context,
+        budget: &Budget,
+        mut trace: Option<&mut Trace>,
+    ) -> Result<Value> {
+        let started = Instant::now();
+        let mut scheduler = Scheduler::new(&self.graph, &self.plan);
+        let mut ready = VecDeque::from(scheduler.start());
+
+        while let Some(node) = ready.pop_front() {
+            let step = self.plan.step(node).unwrap();
+
+            budget.visit()?;
+            let step_started = Instant::now();
+            let (selected, outcome) = step.process(context, budget, trace.is_some())?;
+            self.limits.check_step(step, context)?;
+
+            let mut skipped = Vec::new();
+            ready.extend(scheduler.complete_with(node, &selected, |node| {
+                if let Some(skipped_step) = self.plan.step(node) {
+                    skipped.push(skipped_step.id.to_owned());
                 }
+            }));
+            skipped.sort();
+
+            if let (Some(trace), Some(outcome)) = (trace.as_mut(), outcome) {
+                trace.steps.push(TraceStep {
+                    component_id: step.id.to_owned(),
+                    name: step.name.to_owned(),
+                    outcome,
+                    skipped,
+                    elapsed: step_started.elapsed(),
+                });
             }
         }
 
-        self.output.process(&context)
+        let output = self.finish(context)?;
+        if let Some(trace) = trace {
+            trace.elapsed = started.elapsed();
+        }
+
+        Ok(output)
+    }
+
+    pub async fn process_async(&self, data: Value) -> Result<Value> {
+        let context = self.context(data)?;
+        self.execute_async(&context, &Budget::new(self)).await
+    }
+
+    async fn process_nested_async(&self, data: Value, budget: &Budget) -> Result<Value> {
+        let context = self.context(data)?;
+        self.execute_async(&context, budget).await
+    }
+
+    #[async_recursion]
+    async fn execute_async(&self, context: &Context, budget: &Budget) -> Result<Value> {
+        let mut scheduler = Scheduler::new(&self.graph, &self.plan);
+        let mut ready = VecDeque::from(scheduler.start());
+
+        while let Some(node) = ready.pop_front() {
+            let step = self.plan.step(node).unwrap();
+
+            budget.visit()?;
+            let selected = step.process_async(context, budget).await?;
+            self.limits.check_step(step, context)?;
+
+            ready.extend(scheduler.complete(node, &selected));
+        }
+
+        self.finish_async(context).await
+    }
+
+    fn finish(&self, context: &Context) -> Result<Value> {
+        let output = self.output.process(context)?;
+        self.limits.check_output(&output)?;
+        Ok(output)
+    }
+
+    async fn finish_async(&self, context: &Context) -> Result<Value> {
+        let output = self.output.process_async(context).await?;
+        self.limits.check_output(&output)?;
+        Ok(output)
+    }
+
+    pub fn validate_input(&self, data: &Value) -> Result<()> {
+        let Some(schema) = &self.schema else {
+            return Ok(());
+        };
+
+        let violations = schema.validate(data);
+        if !violations.is_empty() {
+            return Err(WorkflowError::InputInvalid(violations).into());
+        }
+
+        Ok(())
+    }
+
+    fn context(&self, data: Value) -> Result<Context> {
+        self.limits.check_input(&data)?;
+        self.validate_input(&data)?;
+
+        let variables = self
+            .variables
+            .iter()
+            .filter_map(|(name, variable)| {
+                let value = variable.value.to_owned()?;
+                Some((name.to_owned(), value))
+            })
+            .collect::<DashMap<_, _>>();
+
+        Ok(Context::new(data, variables))
+    }
+
+    /// Ids and names of the components, in node order.
+    pub fn components(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.plan
+            .steps()
+            .map(|step| (step.id.as_str(), step.name.as_str()))
+    }
+
+    /// Edges between components, leaving out the synthetic root.
+    pub fn edges(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.graph
+            .edge_indices()
+            .filter_map(|edge| self.graph.edge_endpoints(edge))
+            .filter(|(from, _)| *from != self.plan.root)
+            .map(|(from, to)| (self.graph[from].as_str(), self.graph[to].as_str()))
     }
 
     pub fn validate(&self) -> Result<()> {
+        self.validate_definition()?;
+        self.validate_workflows(&mut Vec::new(), self.max_depth)
+    }
+
+    fn validate_definition(&self) -> Result<()> {
         let cycle = petgraph::algo::is_cyclic_directed(&self.graph);
         if cycle {
             return Err(WorkflowError::CycleDetected.into());
         }
 
-        for node in self.graph.node_indices() {
-            if let Some(Component::Condition(condition)) = self.components.get(&self.graph[node]) {
-                condition.validate()?;
+        for step in self.plan.steps() {
+            match &step.component {
+                Component::Condition(condition) => condition.validate()?,
+                Component::ForEach(for_each) => for_each.workflow.validate_definition()?,
+                Component::DecisionTable(table) => {
+                    let policy = table.definition.hit_policy;
+                    if policy == HitPolicy::Unique {
+                        if let Some((left, right, input)) = table.overlaps().into_iter().next() {
+                            return Err(WorkflowError::DecisionTableOverlap {
+                                component_id: step.id.to_owned(),
+                                rules: (left, right),
+                                input,
+                            }
+                            .into());
+                        }
+                    }
+                    if policy.single() {
+                        if let Some(input) = table.gap() {
+                            return Err(WorkflowError::DecisionTableIncomplete {
+                                component_id: step.id.to_owned(),
+                                input,
+                            }
+                            .into());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mismatches = self.check_types();
+        if !mismatches.is_empty() {
+            return Err(WorkflowError::TypeMismatch(mismatches).into());
+        }
+
+        Ok(())
+    }
+
+    /// Walks the sub-workflows reachable from this workflow, keeping the chain
+    /// of calls in `stack` to detect recursion and enforce the depth limit.
+    fn validate_workflows(
+        &self,
+        stack: &mut Vec<(String, Option<String>)>,
+        max_depth: usize,
+    ) -> Result<()> {
+        for step in self.plan.steps() {
+            let workflow = match &step.component {
+                Component::Workflow(workflow) => workflow,
+                Component::ForEach(for_each) => {
+                    for_each.workflow.validate_workflows(stack, max_depth)?;
+                    continue;
+                }
+                _ => continue,
+            };
+
+            let SubWorkflowDefinition {
+                workflow_id,
+                version,
+                ..
+            } = &workflow.definition;
+            let call = (workflow_id.to_owned(), version.to_owned());
+            if stack.contains(&call) {
+                return Err(WorkflowError::WorkflowRecursion {
+                    component_id: step.id.to_owned(),
+                    workflow_id: workflow_id.to_owned(),
+                }
+                .into());
             }
+            if stack.len() >= max_depth {
+                return Err(WorkflowError::WorkflowDepthExceeded {
+                    workflow_id: workflow_id.to_owned(),
+                    max_depth,
+                }
+                .into());
+            }
+
+            let resolved = workflow.resolve()?;
+            resolved.validate_definition()?;
+
+            stack.push(call);
+            resolved.validate_workflows(stack, max_depth)?;
+            stack.pop();
         }
 
         Ok(())
@@ -97,10 +432,20 @@ impl Workflow {
 mod workflow {
     use super::*;
 
+    const DEFAULT_MAX_DEPTH: usize = 8;
+
     #[derive(Default)]
     pub struct Builder {
         definition: Value,
         output: Value,
+        variables: Value,
+        schema: Value,
+        components: HashMap<String, Box<dyn AsyncComponent>>,
+        resolver: Option<Arc<dyn WorkflowResolver>>,
+        max_depth: Option<usize>,
+        limits: Limits,
+        functions: FunctionRegistry,
+        operators: OperatorRegistry,
     }
 
     impl Builder {
@@ -114,67 +459,283 @@ mod workflow {
             self
         }
 
+        /// Parses the components from YAML.
+        #[cfg(feature = "yaml")]
+        pub fn with_definition_yaml(self, source: &str) -> Result<Self> {
+            let definition = self.functions.scope(|| {
+                self.operators
+                    .scope(|| format::from_yaml::<HashMap<String, ComponentDefinition>>(source))
+            })?;
+            Ok(self.with_definition(definition))
+        }
+
+        #[cfg(feature = "yaml")]
+        pub fn with_output_yaml(self, source: &str) -> Result<Self> {
+            let output = self
+                .functions
+                .scope(|| format::from_yaml::<ruline_output::OutputDefinition>(source))?;
+            Ok(self.with_output(output))
+        }
+
+        /// Parses the components from TOML, one table per component id.
+        #[cfg(feature = "toml")]
+        pub fn with_definition_toml(self, source: &str) -> Result<Self> {
+            let definition = self.functions.scope(|| {
+                self.operators
+                    .scope(|| format::from_toml::<HashMap<String, ComponentDefinition>>(source))
+            })?;
+            Ok(self.with_definition(definition))
+        }
+
+        #[cfg(feature = "toml")]
+        pub fn with_output_toml(self, source: &str) -> Result<Self> {
+            let output = self
+                .functions
+                .scope(|| format::from_toml::<ruline_output::OutputDefinition>(source))?;
+            Ok(self.with_output(output))
+        }
+
+        pub fn with_variables(mut self, variables: Value) -> Self {
+            self.variables = variables;
+            self
+        }
+
+        pub fn with_schema(mut self, schema: Value) -> Self {
+            self.schema = schema;
+            self
+        }
+
+        pub fn with_component(mut self, id: &str, component: Box<dyn AsyncComponent>) -> Self {
+            self.components.insert(id.to_owned(), component);
+            self
+        }
+
+        pub fn with_resolver(mut self, resolver: Arc<dyn WorkflowResolver>) -> Self {
+            self.resolver = Some(resolver);
+            self
+        }
+
+        pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+            self.max_depth = Some(max_depth);
+            self
+        }
+
+        pub fn with_limits(mut self, limits: Limits) -> Self {
+            self.limits = limits;
+            self
+        }
+
+        /// Custom functions the fields of every component can call, nested
+        /// for-each workflows included. Set them before parsing YAML or TOML
+        /// definitions that call them.
+        pub fn with_functions(mut self, functions: FunctionRegistry) -> Self {
+            self.functions = functions;
+            self
+        }
+
+        /// Custom comparison operators the conditions and table tests can use,
+        /// nested for-each workflows included. Set them before parsing YAML or
+        /// TOML definitions that use them.
+        pub fn with_operators(mut self, operators: OperatorRegistry) -> Self {
+            self.operators = operators;
+            self
+        }
+
+        /// Builds the workflow, failing on custom functions and operators
+        /// missing from the registries.
         pub fn build(self) -> Result<Workflow> {
+            let functions = self.functions.to_owned();
+            let operators = self.operators.to_owned();
+            functions.scope(|| operators.scope(|| self.compile()))
+        }
+
+        fn compile(self) -> Result<Workflow> {
+            if let Value::Object(definition) = &self.definition {
+                for (id, component) in definition {
+                    self.limits.check_definition(id, component)?;
+                }
+            }
+            self.limits.check_definition("output", &self.output)?;
+
+            let variables: HashMap<String, VariableDefinition> = match self.variables {
+                Value::Null => HashMap::new(),
+                variables => serde_json::from_value(variables).map_err(WorkflowError::Serde)?,
+            };
+
+            for (name, variable) in &variables {
+                if let Some(value) = &variable.value {
+                    if !variable.variable_type.matches(value) {
+                        return Err(WorkflowError::VariableTypeMismatch {
+                            variable: name.to_owned(),
+                            expected: variable.variable_type,
+                        }
+                        .into());
+                    }
+                }
+            }
+
             let definition: HashMap<String, ComponentDefinition> =
                 serde_json::from_value(self.definition).map_err(WorkflowError::Serde)?;
             let mut components = HashMap::new();
+            let mut names = HashMap::new();
+            let mut policies: HashMap<String, ErrorPolicy> = HashMap::new();
+            let mut joins: HashMap<String, JoinMode> = HashMap::new();
             let mut nodes = HashMap::new();
             let mut graph = DiGraph::new();
 
             let parent_node = graph.add_node("0".to_owned());
 
             for (id, component) in definition {
+                let (on_error, join) = match &component {
+                    ComponentDefinition::Condition { on_error, join, .. }
+                    | ComponentDefinition::Action { on_error, join, .. }
+                    | ComponentDefinition::Workflow { on_error, join, .. }
+                    | ComponentDefinition::ForEach { on_error, join, .. }
+                    | ComponentDefinition::DecisionTable { on_error, join, .. }
+                    | ComponentDefinition::Scorecard { on_error, join, .. } => {
+                        (on_error.to_owned(), *join)
+                    }
+                };
+                if let Some(join) = join {
+                    joins.insert(id.to_owned(), join);
+                }
+                if let Some(on_error) = on_error {
+                    if let (ComponentDefinition::Condition { .. }, ErrorPolicy::Default { .. }) =
+                        (&component, &on_error)
+                    {
+                        return Err(WorkflowError::ErrorPolicyUnsupported(id).into());
+                    }
+                    policies.insert(id.to_owned(), on_error);
+                }
+
                 match component {
-                    ComponentDefinition::Condition { definition, .. } => {
+                    ComponentDefinition::Condition {
+                        name, definition, ..
+                    } => {
                         let condition =
                             Condition::try_from(definition).map_err(WorkflowError::Condition)?; // (1
+                        let condition = condition
+                            .with_id(&id)
+                            .with_functions(&self.functions)
+                            .with_operators(&self.operators);
                         components.insert(id.to_owned(), Component::Condition(condition));
+                        names.insert(id.to_owned(), name);
                         nodes.insert(id.to_owned(), graph.add_node(id));
                     }
-                    ComponentDefinition::Action { definition, .. } => {
-                        let action = Action::try_from(definition).map_err(WorkflowError::Action)?;
+                    ComponentDefinition::Action {
+                        name, definition, ..
+                    } => {
+                        let action = Action::try_from(definition)
+                            .map_err(WorkflowError::Action)?
+                            .with_id(&id)
+                            .with_functions(&self.functions);
+                        let action = match &action.definition {
+                            ActionDefinition::SetVariable { variable, .. } => {
+                                match variables.get(variable) {
+                                    Some(declared) => {
+                                        action.with_variable_type(declared.variable_type.into())
+                                    }
+                                    None => action,
+                                }
+                            }
+                        };
                         components.insert(id.to_owned(), Component::Action(action));
+                        names.insert(id.to_owned(), name);
+                        nodes.insert(id.to_owned(), graph.add_node(id));
+                    }
+                    ComponentDefinition::Workflow {
+                        name, definition, ..
+                    } => {
+                        let resolver = self
+                            .resolver
+                            .to_owned()
+                            .ok_or_else(|| WorkflowError::ResolverMissing(id.to_owned()))?;
+                        let workflow = SubWorkflow::new(&id, definition, resolver, &self.functions);
+                        components.insert(id.to_owned(), Component::Workflow(workflow));
+                        names.insert(id.to_owned(), name);
+                        nodes.insert(id.to_owned(), graph.add_node(id));
+                    }
+                    ComponentDefinition::ForEach {
+                        name, definition, ..
+                    } => {
+                        let mut builder = Workflow::builder()
+                            .with_definition(definition.components.to_owned())
+                            .with_output(definition.output.to_owned());
+                        if let Some(resolver) = &self.resolver {
+                            builder = builder.with_resolver(Arc::clone(resolver));
+                        }
+                        if let Some(max_depth) = self.max_depth {
+                            builder = builder.with_max_depth(max_depth);
+                        }
+                        builder = builder
+                            .with_limits(self.limits.to_owned())
+                            .with_functions(self.functions.to_owned())
+                            .with_operators(self.operators.to_owned());
+
+                        let for_each =
+                            ForEach::new(&id, definition, builder.build()?, &self.functions);
+                        components.insert(id.to_owned(), Component::ForEach(for_each));
+                        names.insert(id.to_owned(), name);
+                        nodes.insert(id.to_owned(), graph.add_node(id));
+                    }
+                    ComponentDefinition::DecisionTable {
+                        name, definition, ..
+                    } => {
+                        let table =
+                            DecisionTable::new(&id, definition, &self.functions, &self.operators)?;
+                        components.insert(id.to_owned(), Component::DecisionTable(table));
+                        names.insert(id.to_owned(), name);
+                        nodes.insert(id.to_owned(), graph.add_node(id));
+                    }
+                    ComponentDefinition::Scorecard {
+                        name, definition, ..
+                    } => {
+                        let scorecard =
+                            Scorecard::new(&id, definition, &self.functions, &self.operators)?;
+                        components.insert(id.to_owned(), Component::Scorecard(scorecard));
+                        names.insert(id.to_owned(), name);
                         nodes.insert(id.to_owned(), graph.add_node(id));
                     }
                 }
             }
 
+            for (id, component) in self.components {
+                if components.contains_key(&id) {
+                    return Err(WorkflowError::DuplicateComponent(id).into());
+                }
+                components.insert(id.to_owned(), Component::Async(component));
+                names.insert(id.to_owned(), id.to_owned());
+                nodes.insert(id.to_owned(), graph.add_node(id));
+            }
+
             for (component_id, component) in components.iter() {
-                match component {
-                    Component::Condition(condition) => {
-                        let node = nodes.get(component_id).unwrap();
-                        for dependency in condition.dependencies() {
-                            let dependency_node = nodes.get(&dependency).ok_or_else(|| {
-                                WorkflowError::DependencyNotFound {
-                                    component_id: component_id.to_owned(),
-                                    dependency,
-                                }
-                            })?;
-                            graph.add_edge(*dependency_node, *node, ());
+                let node = nodes.get(component_id).unwrap();
+                for dependency in component.dependencies() {
+                    let dependency_node = nodes.get(&dependency).ok_or_else(|| {
+                        WorkflowError::DependencyNotFound {
+                            component_id: component_id.to_owned(),
+                            dependency,
                         }
+                    })?;
+                    graph.add_edge(*dependency_node, *node, ());
+                }
 
-                        for dependant in condition.dependants() {
-                            let dependant_node = nodes.get(&dependant).ok_or_else(|| {
-                                WorkflowError::DependantNotFound {
-                                    component_id: component_id.to_owned(),
-                                    dependant,
-                                }
-                            })?;
-                            graph.add_edge(*node, *dependant_node, ());
-                        }
-                    }
-                    Component::Action(action) => {
-                        let node = nodes.get(component_id).unwrap();
-                        for dependency in action.dependencies() {
-                            let dependency_node = nodes.get(&dependency).ok_or_else(|| {
-                                WorkflowError::DependencyNotFound {
-                                    component_id: component_id.to_owned(),
-                                    dependency,
-                                }
+                let mut dependants = component.dependants();
+                if let Some(policy) = policies.get(component_id) {
+                    dependants.extend(policy.dependants().iter().cloned());
+                    dependants.sort();
+                    dependants.dedup();
+                }
+
+                for dependant in dependants {
+                    let dependant_node =
+                        nodes
+                            .get(&dependant)
+                            .ok_or_else(|| WorkflowError::DependantNotFound {
+                                component_id: component_id.to_owned(),
+                                dependant,
                             })?;
-                            graph.add_edge(*dependency_node, *node, ());
-                        }
-                    }
+                    graph.add_edge(*node, *dependant_node, ());
                 }
             }
 
@@ -186,12 +747,32 @@ mod workflow {
                 }
             }
 
-            let output = Output::try_from(self.output).map_err(WorkflowError::Output)?;
+            let output = Output::try_from(self.output)
+                .map_err(WorkflowError::Output)?
+                .with_functions(&self.functions);
+            let schema: Option<Schema> = match self.schema {
+                Value::Null => None,
+                schema => Some(serde_json::from_value(schema).map_err(WorkflowError::Serde)?),
+            };
 
-            Ok(Workflow {
+            let plan = Plan::new(
+                &graph,
+                parent_node,
+                &nodes,
                 components,
+                names,
+                policies,
+                joins,
+            );
+
+            Ok(Workflow {
+                plan,
+                max_depth: self.max_depth.unwrap_or(DEFAULT_MAX_DEPTH),
+                limits: self.limits,
                 graph,
-                variables: DashMap::new(),
+                variables,
+                schema,
+                functions: self.functions,
                 output,
             })
         }
@@ -0,0 +1,295 @@
+use std::{
+    collections::HashMap,
+    io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use ruline_action::ActionDefinition;
+use ruline_context::Context;
+use serde_json::{Map, Value};
+
+use crate::{plan::Step, Component, SubWorkflow, Workflow, WorkflowError};
+
+/// Resource limits enforced while building and processing a workflow.
+/// Unset limits are not enforced.
+#[derive(Debug, Clone, Default)]
+pub struct Limits {
+    /// Components processed by a single execution.
+    pub max_components: Option<usize>,
+    /// Function calls nested inside one another in any field.
+    pub max_function_depth: Option<usize>,
+    /// Length of any array stored in a variable or output.
+    pub max_array_len: Option<usize>,
+    /// Size of the input data serialized as JSON.
+    pub max_input_bytes: Option<usize>,
+    /// Size of the workflow output serialized as JSON.
+    pub max_output_bytes: Option<usize>,
+    /// Wall-clock time allowed for a single execution.
+    pub deadline: Option<Duration>,
+}
+
+impl Limits {
+    pub fn check_definition(&self, location: &str, definition: &Value) -> Result<()> {
+        let Some(limit) = self.max_function_depth else {
+            return Ok(());
+        };
+
+        let depth = function_depth(definition);
+        if depth > limit {
+            return Err(WorkflowError::FunctionDepthExceeded {
+                location: location.to_owned(),
+                depth,
+                limit,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    pub fn check_input(&self, data: &Value) -> Result<()> {
+        let Some(limit) = self.max_input_bytes else {
+            return Ok(());
+        };
+
+        let size = byte_size(data);
+        if size > limit {
+            return Err(WorkflowError::InputTooLarge { size, limit }.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn check_output(&self, output: &Value) -> Result<()> {
+        if let Some(limit) = self.max_output_bytes {
+            let size = byte_size(output);
+            if size > limit {
+                return Err(WorkflowError::OutputTooLarge { size, limit }.into());
+            }
+        }
+
+        self.check_arrays("output", output)
+    }
+
+    /// Checks the variable or output the step has just produced.
+    pub fn check_step(&self, step: &Step, ctx: &Context) -> Result<()> {
+        if self.max_array_len.is_none() {
+            return Ok(());
+        }
+
+        let value = match &step.component {
+            Component::Action(action) => match &action.definition {
+                ActionDefinition::SetVariable { variable, .. } => ctx.get_variable(variable),
+            },
+            _ => ctx.outputs.get(&step.id).map(|output| output.to_owned()),
+        };
+
+        match value {
+            Some(value) => self.check_arrays(&step.id, &value),
+            None => Ok(()),
+        }
+    }
+
+    fn check_arrays(&self, component_id: &str, value: &Value) -> Result<()> {
+        let Some(limit) = self.max_array_len else {
+            return Ok(());
+        };
+
+        let mut stack = vec![value];
+        while let Some(value) = stack.pop() {
+            match value {
+                Value::Array(items) if items.len() > limit => {
+                    return Err(WorkflowError::ArrayTooLarge {
+                        component_id: component_id.to_owned(),
+                        length: items.len(),
+                        limit,
+                    }
+                    .into());
+                }
+                Value::Array(items) => stack.extend(items),
+                Value::Object(map) => stack.extend(map.values()),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Sub-workflow id and version, as a sub-workflow component names them.
+type Target = (String, Option<String>);
+
+/// Tracks the components processed, the time spent and the sub-workflows
+/// entered by one execution. Clones share the count and the sub-workflows
+/// resolved, so the for-each and sub-workflow runs an execution nests are
+/// charged to it and run the same versions.
+#[derive(Debug, Clone)]
+pub struct Budget {
+    max_components: Option<usize>,
+    deadline: Option<Duration>,
+    max_depth: usize,
+    depth: usize,
+    started: Instant,
+    visited: Arc<AtomicUsize>,
+    resolved: Arc<Mutex<HashMap<Target, Arc<Workflow>>>>,
+}
+
+impl Budget {
+    /// Starts an execution of `workflow`, resolving the sub-workflows it can
+    /// reach up front so that activating another version while it runs does
+    /// not change the ones it calls.
+    pub fn new(workflow: &Workflow) -> Self {
+        let budget = Self {
+            max_components: workflow.limits.max_components,
+            deadline: workflow.limits.deadline,
+            max_depth: workflow.max_depth,
+            depth: 0,
+            started: Instant::now(),
+            visited: Arc::new(AtomicUsize::new(0)),
+            resolved: Arc::default(),
+        };
+        budget.pin(workflow, 0);
+        budget
+    }
+
+    /// Resolves the sub-workflows called by `workflow`, down to the maximum
+    /// depth. Those that fail to resolve are left to fail when called.
+    fn pin(&self, workflow: &Workflow, depth: usize) {
+        for step in workflow.plan.steps() {
+            match &step.component {
+                Component::Workflow(sub_workflow) if depth < self.max_depth => {
+                    let target = target(sub_workflow);
+                    if self.resolved.lock().contains_key(&target) {
+                        continue;
+                    }
+                    if let Ok(resolved) = sub_workflow.resolve() {
+                        self.resolved.lock().insert(target, Arc::clone(&resolved));
+                        self.pin(&resolved, depth + 1);
+                    }
+                }
+                Component::ForEach(for_each) => self.pin(&for_each.workflow, depth),
+                _ => {}
+            }
+        }
+    }
+
+    /// The workflow `sub_workflow` calls in this execution, which keeps the
+    /// first version resolved.
+    pub fn resolve(&self, sub_workflow: &SubWorkflow) -> Result<Arc<Workflow>> {
+        let target = target(sub_workflow);
+        if let Some(resolved) = self.resolved.lock().get(&target) {
+            return Ok(Arc::clone(resolved));
+        }
+
+        let resolved = sub_workflow.resolve()?;
+        Ok(Arc::clone(
+            self.resolved.lock().entry(target).or_insert(resolved),
+        ))
+    }
+
+    /// Enters the sub-workflow `workflow_id`. Checked at runtime too, as the
+    /// workflows resolved can change after validation and form a cycle.
+    pub fn nested(&self, workflow_id: &str) -> Result<Self> {
+        if self.depth >= self.max_depth {
+            return Err(WorkflowError::WorkflowDepthExceeded {
+                workflow_id: workflow_id.to_owned(),
+                max_depth: self.max_depth,
+            }
+            .into());
+        }
+
+        Ok(Self {
+            depth: self.depth + 1,
+            ..self.to_owned()
+        })
+    }
+
+    pub fn visit(&self) -> Result<()> {
+        let visited = self.visited.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(limit) = self.max_components {
+            if visited > limit {
+                return Err(WorkflowError::ComponentLimitExceeded(limit).into());
+            }
+        }
+
+        if let Some(deadline) = self.deadline {
+            if self.started.elapsed() > deadline {
+                return Err(WorkflowError::DeadlineExceeded(deadline).into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Deepest chain of `function` fields, walked without recursion so hostile
+/// definitions cannot exhaust the stack. Literals are data rather than
+/// fields, so neither the payload of `value` fields nor the cells of tables
+/// and scorecards are walked.
+fn function_depth(value: &Value) -> usize {
+    let mut deepest = 0;
+    let mut stack = vec![(value, 0)];
+
+    while let Some((value, depth)) = stack.pop() {
+        match value {
+            Value::Object(map) => match map.get("type").and_then(Value::as_str) {
+                Some("value") => {}
+                Some("decision_table" | "scorecard") => {
+                    stack.extend(table_fields(map).map(|field| (field, depth)))
+                }
+                field_type => {
+                    let depth = depth + usize::from(field_type == Some("function"));
+                    deepest = deepest.max(depth);
+                    stack.extend(map.values().map(|value| (value, depth)));
+                }
+            },
+            Value::Array(items) => stack.extend(items.iter().map(|value| (value, depth))),
+            _ => {}
+        }
+    }
+
+    deepest
+}
+
+/// The fields read by the inputs of a decision table or the characteristics
+/// of a scorecard.
+fn table_fields(component: &Map<String, Value>) -> impl Iterator<Item = &Value> {
+    ["inputs", "characteristics"]
+        .into_iter()
+        .filter_map(|key| component.get("definition")?.get(key)?.as_array())
+        .flatten()
+        .filter_map(|row| row.get("field"))
+}
+
+fn target(sub_workflow: &SubWorkflow) -> Target {
+    let definition = &sub_workflow.definition;
+    (
+        definition.workflow_id.to_owned(),
+        definition.version.to_owned(),
+    )
+}
+
+fn byte_size(value: &Value) -> usize {
+    struct Counter(usize);
+
+    impl io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    serde_json::to_writer(&mut counter, value).unwrap_or_default();
+    counter.0
+}
@@ -0,0 +1,320 @@
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    fmt::{self, Display},
+};
+
+use ruline_action::ActionDefinition;
+use ruline_condition::{ConditionDefinition, Expression};
+use ruline_field::FieldDefinition;
+use serde::Serialize;
+
+use crate::{plan::Step, Component, Workflow};
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    UnreachableComponent,
+    UnusedVariable,
+    UndefinedVariable,
+    UnknownResultExpression,
+    DuplicateExpressionId,
+    IncompleteDecisionTable,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct LintWarning {
+    pub kind: LintKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component_id: Option<String>,
+    pub message: String,
+}
+
+impl Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.component_id {
+            Some(component_id) => write!(f, "component `{}`: {}", component_id, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Variables read and written across a workflow and its for-each bodies,
+/// keyed by name. Readers are component ids, `None` standing for the output.
+#[derive(Default)]
+struct Usage {
+    reads: BTreeMap<String, Vec<Option<String>>>,
+    writes: BTreeMap<String, Vec<String>>,
+    opaque: bool,
+}
+
+impl Usage {
+    fn read(&mut self, reader: Option<&str>, field: &FieldDefinition) {
+        for variable in field.variables() {
+            self.reads
+                .entry(variable)
+                .or_default()
+                .push(reader.map(ToOwned::to_owned));
+        }
+    }
+}
+
+fn expressions(condition: &ConditionDefinition) -> &[Expression] {
+    match condition {
+        ConditionDefinition::Binary { expression, .. } => std::slice::from_ref(expression),
+        ConditionDefinition::Decision { expressions, .. } => expressions,
+    }
+}
+
+fn walk<'a>(expression: &'a Expression, visit: &mut impl FnMut(&'a Expression)) {
+    visit(expression);
+    if let Expression::Logical { expressions, .. } = expression {
+        for expression in expressions {
+            walk(expression, visit);
+        }
+    }
+}
+
+/// Result keys of a decision that name none of its top-level expressions, so
+/// their dependants can never be selected.
+fn unknown_results(condition: &ConditionDefinition) -> Vec<&str> {
+    let ConditionDefinition::Decision {
+        expressions,
+        results,
+        ..
+    } = condition
+    else {
+        return vec![];
+    };
+
+    let ids = expressions
+        .iter()
+        .map(Expression::get_id)
+        .collect::<HashSet<_>>();
+    let mut unknown = results
+        .keys()
+        .filter(|key| !ids.contains(*key))
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    unknown.sort();
+    unknown
+}
+
+impl Workflow {
+    /// Reports definitions that are valid but most likely mistakes, such as
+    /// components that can never run. Unlike `validate`, nothing it reports
+    /// prevents the workflow from running.
+    pub fn lint(&self) -> Vec<LintWarning> {
+        let mut warnings = Vec::new();
+        let mut usage = Usage::default();
+        for (name, variable) in &self.variables {
+            if variable.value.is_some() {
+                usage.writes.entry(name.to_owned()).or_default();
+            }
+        }
+
+        self.collect(None, &mut usage, &mut warnings);
+
+        // Async components may read and set any variable.
+        if !usage.opaque {
+            for (variable, writers) in &usage.writes {
+                if usage.reads.contains_key(variable) {
+                    continue;
+                }
+                warnings.extend(writers.iter().map(|writer| LintWarning {
+                    kind: LintKind::UnusedVariable,
+                    component_id: Some(writer.to_owned()),
+                    message: format!("Variable `{}` is set but never read", variable),
+                }));
+            }
+
+            for (variable, readers) in &usage.reads {
+                if usage.writes.contains_key(variable) {
+                    continue;
+                }
+                let mut readers = readers.to_owned();
+                readers.dedup();
+                warnings.extend(readers.into_iter().map(|reader| LintWarning {
+                    kind: LintKind::UndefinedVariable,
+                    message: match &reader {
+                        Some(_) => format!("Variable `{}` is read but never set", variable),
+                        None => format!(
+                            "Variable `{}` is read by the output but never set",
+                            variable
+                        ),
+                    },
+                    component_id: reader,
+                }));
+            }
+        }
+
+        warnings.sort_by(|left, right| {
+            (&left.component_id, left.kind, &left.message).cmp(&(
+                &right.component_id,
+                right.kind,
+                &right.message,
+            ))
+        });
+        warnings.dedup();
+        warnings
+    }
+
+    /// Adds the warnings local to this workflow and its variable usage,
+    /// prefixing component ids with the enclosing for-each, if any.
+    fn collect(&self, prefix: Option<&str>, usage: &mut Usage, warnings: &mut Vec<LintWarning>) {
+        let qualify = |id: &str| match prefix {
+            Some(prefix) => format!("{}/{}", prefix, id),
+            None => id.to_owned(),
+        };
+
+        for step in self.plan.steps() {
+            let id = qualify(&step.id);
+            match &step.component {
+                Component::Condition(condition) => {
+                    let definition = condition.definition();
+                    for key in unknown_results(definition) {
+                        warnings.push(LintWarning {
+                            kind: LintKind::UnknownResultExpression,
+                            component_id: Some(id.to_owned()),
+                            message: format!("Result key `{}` does not match any expression", key),
+                        });
+                    }
+
+                    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+                    for expression in expressions(definition) {
+                        walk(expression, &mut |expression| {
+                            *counts.entry(expression.get_id()).or_default() += 1;
+                            if let Expression::Comparison { operands, .. } = expression {
+                                for operand in operands {
+                                    usage.read(Some(&id), operand);
+                                }
+                            }
+                        });
+                    }
+                    warnings.extend(counts.into_iter().filter(|(_, count)| *count > 1).map(
+                        |(expression_id, count)| LintWarning {
+                            kind: LintKind::DuplicateExpressionId,
+                            component_id: Some(id.to_owned()),
+                            message: format!(
+                                "Expression id `{}` is used {} times",
+                                expression_id, count
+                            ),
+                        },
+                    ));
+                }
+                Component::Action(action) => {
+                    let ActionDefinition::SetVariable { variable, value } = &action.definition;
+                    usage.read(Some(&id), value);
+                    usage
+                        .writes
+                        .entry(variable.to_owned())
+                        .or_default()
+                        .push(id.to_owned());
+                }
+                Component::Workflow(workflow) => {
+                    for value in workflow.definition.input.values() {
+                        usage.read(Some(&id), value);
+                    }
+                }
+                Component::ForEach(for_each) => {
+                    usage.read(Some(&id), &for_each.definition.items);
+                    for_each.workflow.collect(Some(&id), usage, warnings);
+                }
+                Component::DecisionTable(table) => {
+                    for input in &table.definition.inputs {
+                        usage.read(Some(&id), &input.field);
+                    }
+                    if let Some(input) = table.gap() {
+                        warnings.push(LintWarning {
+                            kind: LintKind::IncompleteDecisionTable,
+                            component_id: Some(id.to_owned()),
+                            message: format!("No rule matches input {}", input),
+                        });
+                    }
+                }
+                Component::Scorecard(scorecard) => {
+                    for characteristic in &scorecard.definition.characteristics {
+                        usage.read(Some(&id), &characteristic.field);
+                    }
+                }
+                Component::Async(_) => usage.opaque = true,
+            }
+        }
+
+        for (_, field) in self.output.definition.fields() {
+            usage.read(prefix, field);
+        }
+
+        let reachable = self.reachable();
+        warnings.extend(
+            self.plan
+                .steps()
+                .filter(|step| !reachable.contains(step.id.as_str()))
+                .map(|step| LintWarning {
+                    kind: LintKind::UnreachableComponent,
+                    component_id: Some(qualify(&step.id)),
+                    message: "Component is never reached from the root".to_owned(),
+                }),
+        );
+    }
+
+    /// Ids of the components some execution can reach from the root, leaving
+    /// out the dependants a decision only selects through unknown result keys.
+    fn reachable(&self) -> HashSet<&str> {
+        let mut reachable = HashSet::new();
+        let mut queue = VecDeque::from([self.plan.root]);
+        let mut visited = HashSet::from([self.plan.root]);
+
+        while let Some(node) = queue.pop_front() {
+            let dead = self.plan.step(node).map(|step| self.dead_dependants(step));
+            for next in self.graph.neighbors(node) {
+                let id = self.graph[next].as_str();
+                if dead.as_ref().is_some_and(|dead| dead.contains(id)) {
+                    continue;
+                }
+                if visited.insert(next) {
+                    reachable.insert(id);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        reachable
+    }
+
+    fn dead_dependants(&self, step: &Step) -> HashSet<String> {
+        let Component::Condition(condition) = &step.component else {
+            return HashSet::new();
+        };
+        let ConditionDefinition::Decision {
+            results, fallbacks, ..
+        } = condition.definition()
+        else {
+            return HashSet::new();
+        };
+
+        let unknown = unknown_results(condition.definition());
+        let mut live = results
+            .iter()
+            .filter(|(key, _)| !unknown.contains(&key.as_str()))
+            .flat_map(|(_, dependants)| dependants.iter())
+            .chain(fallbacks)
+            .cloned()
+            .collect::<HashSet<_>>();
+        if let Some(policy) = &step.policy {
+            live.extend(policy.dependants().iter().cloned());
+        }
+
+        let readers = self
+            .plan
+            .steps()
+            .filter(|other| other.component.dependencies().contains(&step.id))
+            .map(|other| other.id.as_str())
+            .collect::<HashSet<_>>();
+        unknown
+            .into_iter()
+            .flat_map(|key| results[key].iter())
+            .filter(|id| !live.contains(*id) && !readers.contains(id.as_str()))
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,114 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use anyhow::Result;
+use petgraph::graph::NodeIndex;
+use serde_json::Value;
+use tokio::task::JoinSet;
+
+use crate::{limits::Budget, schedule::Scheduler, Workflow};
+
+impl Workflow {
+    pub fn process_parallel(&self, data: Value) -> Result<Value> {
+        let context = self.context(data)?;
+        let workers = thread::available_parallelism()
+            .map(NonZeroUsize::get)
+            .unwrap_or(1)
+            .min(self.graph.node_count());
+
+        let (job_sender, job_receiver) = mpsc::channel::<NodeIndex>();
+        let (result_sender, result_receiver) = mpsc::channel();
+        let job_receiver = Mutex::new(job_receiver);
+        let budget = Budget::new(self);
+
+        thread::scope(|scope| -> Result<()> {
+            let job_sender = job_sender;
+
+            for _ in 0..workers {
+                let job_receiver = &job_receiver;
+                let result_sender = result_sender.clone();
+                let context = &context;
+                let budget = &budget;
+
+                scope.spawn(move || loop {
+                    let job = job_receiver.lock().unwrap().recv();
+                    let Ok(node) = job else {
+                        break;
+                    };
+
+                    let step = self.plan.step(node).unwrap();
+                    if result_sender
+                        .send((node, step.process(context, budget, false)))
+                        .is_err()
+                    {
+                        break;
+                    }
+                });
+            }
+            drop(result_sender);
+
+            let mut scheduler = Scheduler::new(&self.graph, &self.plan);
+            let mut in_flight = 0;
+            for node in scheduler.start() {
+                budget.visit()?;
+                job_sender.send(node)?;
+                in_flight += 1;
+            }
+
+            while in_flight > 0 {
+                let (node, selected) = result_receiver.recv()?;
+                in_flight -= 1;
+
+                let (selected, _) = selected?;
+                self.limits
+                    .check_step(self.plan.step(node).unwrap(), &context)?;
+                for node in scheduler.complete(node, &selected) {
+                    budget.visit()?;
+                    job_sender.send(node)?;
+                    in_flight += 1;
+                }
+            }
+
+            Ok(())
+        })?;
+
+        self.finish(&context)
+    }
+
+    pub async fn process_parallel_async(self: Arc<Self>, data: Value) -> Result<Value> {
+        let context = Arc::new(self.context(data)?);
+        let budget = Budget::new(&self);
+        let mut scheduler = Scheduler::new(&self.graph, &self.plan);
+        let mut tasks = JoinSet::new();
+
+        let mut ready = scheduler.start();
+        loop {
+            for node in ready.drain(..) {
+                budget.visit()?;
+                let workflow = Arc::clone(&self);
+                let context = Arc::clone(&context);
+                let budget = budget.clone();
+
+                tasks.spawn(async move {
+                    let step = workflow.plan.step(node).unwrap();
+                    let selected = step.process_async(&context, &budget).await?;
+                    anyhow::Ok((node, selected))
+                });
+            }
+
+            let Some(joined) = tasks.join_next().await else {
+                break;
+            };
+
+            let (node, selected) = joined??;
+            self.limits
+                .check_step(self.plan.step(node).unwrap(), &context)?;
+            ready = scheduler.complete(node, &selected);
+        }
+
+        self.finish_async(&context).await
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use petgraph::graph::{DiGraph, NodeIndex};
+use ruline_action::ActionDefinition;
+use ruline_context::Context;
+use serde_json::json;
+
+use crate::{limits::Budget, Component, ErrorPolicy, JoinMode, TraceOutcome, WorkflowError};
+
+#[derive(Debug)]
+pub struct Step {
+    pub id: String,
+    pub name: String,
+    pub component: Component,
+    pub policy: Option<ErrorPolicy>,
+    pub join: JoinMode,
+    dependants: Vec<(String, NodeIndex)>,
+}
+
+impl Step {
+    /// Processes the component, applying the error policy of the step when
+    /// it fails. Returns the dependants to run next along with the outcome,
+    /// when `traced`.
+    pub fn process(
+        &self,
+        ctx: &Context,
+        budget: &Budget,
+        traced: bool,
+    ) -> Result<(Vec<String>, Option<TraceOutcome>)> {
+        match self.process_component(ctx, budget, traced) {
+            Ok(processed) => Ok(processed),
+            Err(err) => {
+                let message = err.to_string();
+                let selected = self.recover(ctx, err)?;
+                Ok((selected, traced.then_some(TraceOutcome::Error { message })))
+            }
+        }
+    }
+
+    pub async fn process_async(&self, ctx: &Context, budget: &Budget) -> Result<Vec<String>> {
+        let processed = match &self.component {
+            Component::Condition(condition) => condition.evaluate_async(ctx).await,
+            Component::Action(action) => action.process_async(ctx).await.map(|_| vec![]),
+            Component::Workflow(workflow) => workflow.run_async(ctx, budget).await.map(|_| vec![]),
+            Component::ForEach(for_each) => for_each.run_async(ctx, budget).await.map(|_| vec![]),
+            Component::DecisionTable(table) => table.process_async(ctx).await.map(|_| vec![]),
+            Component::Scorecard(scorecard) => scorecard.process_async(ctx).await.map(|_| vec![]),
+            Component::Async(component) => component.process(ctx).await,
+        };
+
+        match processed {
+            Ok(selected) => Ok(selected),
+            Err(err) => self.recover(ctx, err),
+        }
+    }
+
+    fn process_component(
+        &self,
+        ctx: &Context,
+        budget: &Budget,
+        traced: bool,
+    ) -> Result<(Vec<String>, Option<TraceOutcome>)> {
+        let processed = match &self.component {
+            Component::Condition(condition) if traced => {
+                let (selected, condition_trace) = condition.evaluate_with_trace(ctx)?;
+                (selected, Some(TraceOutcome::Condition(condition_trace)))
+            }
+            Component::Condition(condition) => (condition.evaluate(ctx)?, None),
+            Component::Action(action) => {
+                action.process(ctx)?;
+                let outcome = traced.then(|| match &action.definition {
+                    ActionDefinition::SetVariable { variable, .. } => TraceOutcome::Action {
+                        variable: variable.to_owned(),
+                        value: ctx.get_variable(variable).unwrap_or_default(),
+                    },
+                });
+                (vec![], outcome)
+            }
+            Component::Workflow(workflow) => {
+                let output = workflow.run(ctx, budget)?;
+                let outcome = traced.then(|| TraceOutcome::Workflow {
+                    workflow_id: workflow.definition.workflow_id.to_owned(),
+                    output,
+                });
+                (vec![], outcome)
+            }
+            Component::ForEach(for_each) => {
+                let output = for_each.run(ctx, budget)?;
+                let outcome = traced.then_some(TraceOutcome::ForEach { output });
+                (vec![], outcome)
+            }
+            Component::DecisionTable(table) => {
+                let (rules, output) = table.evaluate(ctx)?;
+                let outcome = traced.then_some(TraceOutcome::DecisionTable { rules, output });
+                (vec![], outcome)
+            }
+            Component::Scorecard(scorecard) => {
+                let output = scorecard.process(ctx)?;
+                let outcome = traced.then_some(TraceOutcome::Scorecard { output });
+                (vec![], outcome)
+            }
+            Component::Async(_) => {
+                return Err(WorkflowError::AsyncComponent(self.id.to_owned()).into())
+            }
+        };
+
+        Ok(processed)
+    }
+
+    /// Applies the error policy of the step to `err`, returning the
+    /// dependants to run next or the error when the policy is to fail.
+    fn recover(&self, ctx: &Context, err: anyhow::Error) -> Result<Vec<String>> {
+        let policy = match &self.policy {
+            None | Some(ErrorPolicy::Fail) => return Err(err),
+            Some(policy) => policy,
+        };
+
+        ctx.set_output(
+            self.id.to_owned(),
+            json!({ "error": { "message": err.to_string() } }),
+        );
+
+        match policy {
+            ErrorPolicy::Default { value } => match &self.component {
+                Component::Action(action) => match &action.definition {
+                    ActionDefinition::SetVariable { variable, .. } => {
+                        action.check(value)?;
+                        ctx.set_variable(variable.to_owned(), value.to_owned())
+                    }
+                },
+                _ => ctx.set_output(self.id.to_owned(), value.to_owned()),
+            },
+            ErrorPolicy::Route { dependants } => return Ok(dependants.to_owned()),
+            ErrorPolicy::Fail | ErrorPolicy::Skip => {}
+        }
+
+        Ok(vec![])
+    }
+
+    pub fn skipped<'a>(&'a self, selected: &'a [String]) -> impl Iterator<Item = NodeIndex> + 'a {
+        self.dependants
+            .iter()
+            .filter(|(id, _)| !selected.contains(id))
+            .map(|(_, node)| *node)
+    }
+}
+
+/// Immutable execution plan compiled from the workflow graph, with one step
+/// per node index so processing never has to look components up by id.
+#[derive(Debug)]
+pub struct Plan {
+    pub root: NodeIndex,
+    steps: Vec<Option<Step>>,
+}
+
+impl Plan {
+    pub fn new(
+        graph: &DiGraph<String, ()>,
+        root: NodeIndex,
+        nodes: &HashMap<String, NodeIndex>,
+        mut components: HashMap<String, Component>,
+        mut names: HashMap<String, String>,
+        mut policies: HashMap<String, ErrorPolicy>,
+        mut joins: HashMap<String, JoinMode>,
+    ) -> Self {
+        let steps = graph
+            .node_indices()
+            .map(|node| {
+                let id = &graph[node];
+                let component = components.remove(id)?;
+                let policy = policies.remove(id);
+                let mut dependants = component.dependants();
+                if let Some(policy) = &policy {
+                    dependants.extend(policy.dependants().iter().cloned());
+                    dependants.sort();
+                    dependants.dedup();
+                }
+
+                let dependants = dependants
+                    .into_iter()
+                    .map(|dependant| {
+                        let node = nodes[&dependant];
+                        (dependant, node)
+                    })
+                    .collect();
+
+                Some(Step {
+                    id: id.to_owned(),
+                    name: names.remove(id).unwrap_or_default(),
+                    component,
+                    policy,
+                    join: joins.remove(id).unwrap_or_default(),
+                    dependants,
+                })
+            })
+            .collect();
+
+        Plan { root, steps }
+    }
+
+    pub fn step(&self, node: NodeIndex) -> Option<&Step> {
+        self.steps[node.index()].as_ref()
+    }
+
+    pub fn steps(&self) -> impl Iterator<Item = &Step> {
+        self.steps.iter().flatten()
+    }
+}
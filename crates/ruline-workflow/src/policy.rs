@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// What to do when processing a component fails. Every policy other than
+/// `fail` publishes `{ "error": { "message": ... } }` as the output of the
+/// failed component so the rest of the workflow can read it.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum ErrorPolicy {
+    /// Abort the whole workflow with the error.
+    Fail,
+    /// Run the given components instead of the regular dependants.
+    Route { dependants: Vec<String> },
+    /// Set the action variable, or the component output, to `value`.
+    Default { value: Value },
+    /// Carry on as if the component had selected no dependants.
+    Skip,
+}
+
+impl ErrorPolicy {
+    pub fn dependants(&self) -> &[String] {
+        match self {
+            ErrorPolicy::Route { dependants } => dependants,
+            _ => &[],
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Weak},
+};
+
+use anyhow::Result;
+use parking_lot::RwLock;
+use ruline_condition::OperatorRegistry;
+use ruline_field::FunctionRegistry;
+
+use crate::{Limits, Workflow, WorkflowError, WorkflowResolver, WorkflowSpec};
+
+/// Loads workflow definitions for the registry, e.g. from a database or the
+/// file system.
+pub trait WorkflowSource: Send + Sync {
+    fn load(&self, workflow_id: &str, version: &str) -> Result<WorkflowSpec>;
+}
+
+type Key = (String, String);
+
+struct Inner {
+    source: Box<dyn WorkflowSource>,
+    limits: Limits,
+    max_depth: Option<usize>,
+    functions: FunctionRegistry,
+    operators: OperatorRegistry,
+    workflows: RwLock<HashMap<Key, Arc<Workflow>>>,
+    active: RwLock<HashMap<String, String>>,
+}
+
+/// Compiled workflows keyed by id and version, with one active version per
+/// workflow. Lookups hand out `Arc`s, so replacing the active version never
+/// affects executions already holding the previous one.
+///
+/// Sub-workflows are resolved through the registry itself, by the version
+/// they pin or else by the active one. An execution resolves them when it
+/// starts and keeps those versions until it finishes.
+#[derive(Clone)]
+pub struct WorkflowRegistry {
+    inner: Arc<Inner>,
+}
+
+impl WorkflowRegistry {
+    pub fn new(source: impl WorkflowSource + 'static) -> Self {
+        Self::builder(source).build()
+    }
+
+    pub fn builder(source: impl WorkflowSource + 'static) -> Builder {
+        Builder {
+            source: Box::new(source),
+            limits: Limits::default(),
+            max_depth: None,
+            functions: FunctionRegistry::default(),
+            operators: OperatorRegistry::default(),
+        }
+    }
+
+    /// Returns the given version of a workflow, loading and compiling it from
+    /// the source the first time it is requested.
+    pub fn load(&self, workflow_id: &str, version: &str) -> Result<Arc<Workflow>> {
+        let key = (workflow_id.to_owned(), version.to_owned());
+        if let Some(workflow) = self.inner.workflows.read().get(&key) {
+            return Ok(Arc::clone(workflow));
+        }
+
+        let workflow = Arc::new(self.build(self.inner.source.load(workflow_id, version)?)?);
+        let mut workflows = self.inner.workflows.write();
+        Ok(Arc::clone(workflows.entry(key).or_insert(workflow)))
+    }
+
+    /// Returns the given version of a workflow, or its active version.
+    pub fn get(&self, workflow_id: &str, version: Option<&str>) -> Result<Arc<Workflow>> {
+        match version {
+            Some(version) => self.load(workflow_id, version),
+            None => {
+                let version = self
+                    .active_version(workflow_id)
+                    .ok_or_else(|| WorkflowError::WorkflowNotActive(workflow_id.to_owned()))?;
+                self.load(workflow_id, &version)
+            }
+        }
+    }
+
+    /// Loads and validates the given version, then makes it the active one in
+    /// a single step, returning the version it replaced.
+    pub fn activate(&self, workflow_id: &str, version: &str) -> Result<Option<String>> {
+        self.load(workflow_id, version)?.validate()?;
+
+        let mut active = self.inner.active.write();
+        Ok(active.insert(workflow_id.to_owned(), version.to_owned()))
+    }
+
+    pub fn active_version(&self, workflow_id: &str) -> Option<String> {
+        self.inner.active.read().get(workflow_id).cloned()
+    }
+
+    /// Drops the compiled version so it is loaded again from the source on the
+    /// next request. The active version cannot be evicted.
+    pub fn evict(&self, workflow_id: &str, version: &str) -> Result<()> {
+        let active = self.inner.active.read();
+        if active.get(workflow_id).map(String::as_str) == Some(version) {
+            return Err(WorkflowError::WorkflowActive {
+                workflow_id: workflow_id.to_owned(),
+                version: version.to_owned(),
+            }
+            .into());
+        }
+
+        let key = (workflow_id.to_owned(), version.to_owned());
+        self.inner.workflows.write().remove(&key);
+        Ok(())
+    }
+
+    fn build(&self, spec: WorkflowSpec) -> Result<Workflow> {
+        let resolver = Arc::new(Resolver(Arc::downgrade(&self.inner)));
+        let mut builder = Workflow::builder()
+            .with_definition(spec.definition)
+            .with_output(spec.output)
+            .with_variables(spec.variables)
+            .with_schema(spec.schema)
+            .with_resolver(resolver)
+            .with_limits(self.inner.limits.to_owned())
+            .with_functions(self.inner.functions.to_owned())
+            .with_operators(self.inner.operators.to_owned());
+        if let Some(max_depth) = self.inner.max_depth {
+            builder = builder.with_max_depth(max_depth);
+        }
+
+        builder.build()
+    }
+}
+
+pub struct Builder {
+    source: Box<dyn WorkflowSource>,
+    limits: Limits,
+    max_depth: Option<usize>,
+    functions: FunctionRegistry,
+    operators: OperatorRegistry,
+}
+
+impl Builder {
+    /// Limits applied to every workflow the registry compiles.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Custom functions available to every workflow the registry compiles.
+    pub fn with_functions(mut self, functions: FunctionRegistry) -> Self {
+        self.functions = functions;
+        self
+    }
+
+    /// Custom comparison operators available to every workflow the registry
+    /// compiles.
+    pub fn with_operators(mut self, operators: OperatorRegistry) -> Self {
+        self.operators = operators;
+        self
+    }
+
+    pub fn build(self) -> WorkflowRegistry {
+        WorkflowRegistry {
+            inner: Arc::new(Inner {
+                source: self.source,
+                limits: self.limits,
+                max_depth: self.max_depth,
+                functions: self.functions,
+                operators: self.operators,
+                workflows: RwLock::new(HashMap::new()),
+                active: RwLock::new(HashMap::new()),
+            }),
+        }
+    }
+}
+
+impl WorkflowResolver for WorkflowRegistry {
+    fn resolve(&self, workflow_id: &str, version: Option<&str>) -> Result<Arc<Workflow>> {
+        self.get(workflow_id, version)
+    }
+}
+
+/// Resolver handed to the workflows of a registry. It only keeps a weak
+/// reference so that compiled workflows do not keep their registry alive.
+struct Resolver(Weak<Inner>);
+
+impl WorkflowResolver for Resolver {
+    fn resolve(&self, workflow_id: &str, version: Option<&str>) -> Result<Arc<Workflow>> {
+        let inner = self.0.upgrade().ok_or(WorkflowError::RegistryDropped)?;
+        WorkflowRegistry { inner }.get(workflow_id, version)
+    }
+}
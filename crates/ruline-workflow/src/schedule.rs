@@ -0,0 +1,109 @@
+use petgraph::{
+    graph::{DiGraph, NodeIndex},
+    Direction,
+};
+
+use crate::{plan::Plan, JoinMode};
+
+/// Tracks which nodes are ready to run. A node is resolved once all of its
+/// parents are resolved; whether it then runs or is skipped depends on which
+/// parents selected or rejected it and on its join mode.
+pub struct Scheduler<'a> {
+    graph: &'a DiGraph<String, ()>,
+    plan: &'a Plan,
+    pending: Vec<usize>,
+    selected: Vec<bool>,
+    rejected: Vec<bool>,
+}
+
+impl<'a> Scheduler<'a> {
+    pub fn new(graph: &'a DiGraph<String, ()>, plan: &'a Plan) -> Self {
+        let pending = graph
+            .node_indices()
+            .map(|node| graph.edges_directed(node, Direction::Incoming).count())
+            .collect();
+
+        Self {
+            graph,
+            plan,
+            pending,
+            selected: vec![false; graph.node_count()],
+            rejected: vec![false; graph.node_count()],
+        }
+    }
+
+    pub fn start(&mut self) -> Vec<NodeIndex> {
+        self.complete(self.plan.root, &[])
+    }
+
+    pub fn complete(&mut self, node: NodeIndex, selected: &[String]) -> Vec<NodeIndex> {
+        self.complete_with(node, selected, |_| {})
+    }
+
+    /// Resolves the children of `node` after it ran and selected `selected`,
+    /// returning the nodes ready to run and reporting every node skipped as a
+    /// consequence to `on_skip`.
+    pub fn complete_with(
+        &mut self,
+        node: NodeIndex,
+        selected: &[String],
+        mut on_skip: impl FnMut(NodeIndex),
+    ) -> Vec<NodeIndex> {
+        let rejected = self
+            .plan
+            .step(node)
+            .map(|step| step.skipped(selected).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let mut ready = Vec::new();
+        let graph = self.graph;
+        for child in graph.neighbors(node) {
+            match rejected.contains(&child) {
+                true => self.rejected[child.index()] = true,
+                false => self.selected[child.index()] = true,
+            }
+            self.resolve(child, &mut ready, &mut on_skip);
+        }
+        ready
+    }
+
+    fn skip(
+        &mut self,
+        node: NodeIndex,
+        ready: &mut Vec<NodeIndex>,
+        on_skip: &mut impl FnMut(NodeIndex),
+    ) {
+        on_skip(node);
+        let graph = self.graph;
+        for child in graph.neighbors(node) {
+            self.resolve(child, ready, on_skip);
+        }
+    }
+
+    fn resolve(
+        &mut self,
+        node: NodeIndex,
+        ready: &mut Vec<NodeIndex>,
+        on_skip: &mut impl FnMut(NodeIndex),
+    ) {
+        let index = node.index();
+        self.pending[index] -= 1;
+        if self.pending[index] > 0 {
+            return;
+        }
+
+        let join = self
+            .plan
+            .step(node)
+            .map_or(JoinMode::default(), |step| step.join);
+        let runs = match join {
+            JoinMode::All => self.selected[index] && !self.rejected[index],
+            JoinMode::Any => self.selected[index],
+        };
+
+        match runs {
+            true => ready.push(node),
+            false => self.skip(node, ready, on_skip),
+        }
+    }
+}
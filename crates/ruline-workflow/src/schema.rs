@@ -0,0 +1,228 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaType {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Array,
+    Object,
+    Null,
+}
+
+impl SchemaType {
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            SchemaType::String => value.is_string(),
+            SchemaType::Number => value.is_number(),
+            SchemaType::Integer => value.as_f64().is_some_and(|number| number.fract() == 0.0),
+            SchemaType::Boolean => value.is_boolean(),
+            SchemaType::Array => value.is_array(),
+            SchemaType::Object => value.is_object(),
+            SchemaType::Null => value.is_null(),
+        }
+    }
+}
+
+impl Display for SchemaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SchemaType::String => "string",
+            SchemaType::Number => "number",
+            SchemaType::Integer => "integer",
+            SchemaType::Boolean => "boolean",
+            SchemaType::Array => "array",
+            SchemaType::Object => "object",
+            SchemaType::Null => "null",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Subset of JSON Schema used to declare the shape of the input data.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Schema {
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub schema_type: Option<SchemaType>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, Schema>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required: Vec<String>,
+    #[serde(rename = "enum", default, skip_serializing_if = "Option::is_none")]
+    pub enum_values: Option<Vec<Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<Box<Schema>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_items: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_items: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum ViolationKind {
+    Missing,
+    TypeMismatch { expected: SchemaType },
+    NotInEnum { allowed: Vec<Value> },
+    BelowMinimum { minimum: f64 },
+    AboveMaximum { maximum: f64 },
+    TooShort { min_length: usize },
+    TooLong { max_length: usize },
+    TooFewItems { min_items: usize },
+    TooManyItems { max_items: usize },
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SchemaViolation {
+    pub path: String,
+    #[serde(flatten)]
+    pub kind: ViolationKind,
+}
+
+impl Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = match self.path.is_empty() {
+            true => "/",
+            false => &self.path,
+        };
+        match &self.kind {
+            ViolationKind::Missing => write!(f, "`{}` is required", path),
+            ViolationKind::TypeMismatch { expected } => {
+                write!(f, "`{}` must be of type `{}`", path, expected)
+            }
+            ViolationKind::NotInEnum { allowed } => {
+                let allowed = allowed.iter().map(Value::to_string).collect::<Vec<_>>();
+                write!(f, "`{}` must be one of {}", path, allowed.join(", "))
+            }
+            ViolationKind::BelowMinimum { minimum } => {
+                write!(f, "`{}` must be at least {}", path, minimum)
+            }
+            ViolationKind::AboveMaximum { maximum } => {
+                write!(f, "`{}` must be at most {}", path, maximum)
+            }
+            ViolationKind::TooShort { min_length } => {
+                write!(
+                    f,
+                    "`{}` must be at least {} characters long",
+                    path, min_length
+                )
+            }
+            ViolationKind::TooLong { max_length } => {
+                write!(
+                    f,
+                    "`{}` must be at most {} characters long",
+                    path, max_length
+                )
+            }
+            ViolationKind::TooFewItems { min_items } => {
+                write!(f, "`{}` must have at least {} items", path, min_items)
+            }
+            ViolationKind::TooManyItems { max_items } => {
+                write!(f, "`{}` must have at most {} items", path, max_items)
+            }
+        }
+    }
+}
+
+impl Schema {
+    pub fn validate(&self, value: &Value) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+        self.validate_at("", value, &mut violations);
+        violations
+    }
+
+    fn validate_at(&self, path: &str, value: &Value, violations: &mut Vec<SchemaViolation>) {
+        let mut violation = |kind| {
+            violations.push(SchemaViolation {
+                path: path.to_owned(),
+                kind,
+            })
+        };
+
+        if let Some(expected) = self.schema_type {
+            if !expected.matches(value) {
+                violation(ViolationKind::TypeMismatch { expected });
+                return;
+            }
+        }
+
+        if let Some(allowed) = &self.enum_values {
+            if !allowed.contains(value) {
+                violation(ViolationKind::NotInEnum {
+                    allowed: allowed.to_owned(),
+                });
+            }
+        }
+
+        match value {
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+                if let Some(minimum) = self.minimum.filter(|minimum| number < *minimum) {
+                    violation(ViolationKind::BelowMinimum { minimum });
+                }
+                if let Some(maximum) = self.maximum.filter(|maximum| number > *maximum) {
+                    violation(ViolationKind::AboveMaximum { maximum });
+                }
+            }
+            Value::String(string) => {
+                let length = string.chars().count();
+                if let Some(min_length) = self.min_length.filter(|min| length < *min) {
+                    violation(ViolationKind::TooShort { min_length });
+                }
+                if let Some(max_length) = self.max_length.filter(|max| length > *max) {
+                    violation(ViolationKind::TooLong { max_length });
+                }
+            }
+            Value::Array(items) => {
+                if let Some(min_items) = self.min_items.filter(|min| items.len() < *min) {
+                    violation(ViolationKind::TooFewItems { min_items });
+                }
+                if let Some(max_items) = self.max_items.filter(|max| items.len() > *max) {
+                    violation(ViolationKind::TooManyItems { max_items });
+                }
+                if let Some(schema) = &self.items {
+                    for (index, item) in items.iter().enumerate() {
+                        schema.validate_at(&format!("{}/{}", path, index), item, violations);
+                    }
+                }
+            }
+            Value::Object(map) => {
+                for required in &self.required {
+                    if !map.contains_key(required) {
+                        violations.push(SchemaViolation {
+                            path: pointer(path, required),
+                            kind: ViolationKind::Missing,
+                        });
+                    }
+                }
+                for (key, schema) in &self.properties {
+                    if let Some(property) = map.get(key) {
+                        schema.validate_at(&pointer(path, key), property, violations);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn pointer(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
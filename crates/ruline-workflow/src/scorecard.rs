@@ -0,0 +1,217 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use ruline_condition::OperatorRegistry;
+use ruline_context::Context;
+use ruline_field::{Field, FieldDefinition, FunctionRegistry};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Number, Value};
+
+use crate::{table, AsyncComponent, TableTest, WorkflowError};
+
+/// Points given to a characteristic whose value passes every test, an empty
+/// list of tests matching any value.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ScorecardBin {
+    pub tests: Vec<TableTest>,
+    pub points: Number,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Characteristic {
+    pub name: String,
+    pub field: FieldDefinition,
+    pub bins: Vec<ScorecardBin>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ScorecardDefinition {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_points: Option<Number>,
+    pub characteristics: Vec<Characteristic>,
+    /// Reason codes reported at most, 0 reporting none.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub max_reason_codes: usize,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+/// Scores each characteristic with the points of the first bin its value
+/// falls in and publishes under the component id the `score`, which adds up
+/// the base points and the points of every characteristic, the `points` of
+/// each characteristic by name, and the `reason_codes` of the bins that
+/// contributed the most points.
+#[derive(Debug)]
+pub struct Scorecard {
+    pub id: String,
+    pub definition: ScorecardDefinition,
+    fields: Vec<Field>,
+    operators: OperatorRegistry,
+}
+
+impl Scorecard {
+    pub fn new(
+        id: &str,
+        definition: ScorecardDefinition,
+        functions: &FunctionRegistry,
+        operators: &OperatorRegistry,
+    ) -> Result<Self, WorkflowError> {
+        let invalid = |message: String| WorkflowError::ScorecardInvalid {
+            component_id: id.to_owned(),
+            message,
+        };
+
+        for characteristic in &definition.characteristics {
+            if characteristic.bins.is_empty() {
+                return Err(invalid(format!(
+                    "Characteristic `{}` has no bins",
+                    characteristic.name
+                )));
+            }
+
+            for (index, bin) in characteristic.bins.iter().enumerate() {
+                let arity_error = bin
+                    .tests
+                    .iter()
+                    .find_map(|test| test.arity_error(operators));
+                if let Some(message) = arity_error {
+                    return Err(invalid(format!(
+                        "Bin {} of characteristic `{}` {}",
+                        index + 1,
+                        characteristic.name,
+                        message
+                    )));
+                }
+            }
+        }
+
+        let fields = definition
+            .characteristics
+            .iter()
+            .map(|characteristic| Field::with_functions(&characteristic.field, functions))
+            .collect();
+
+        Ok(Self {
+            id: id.to_owned(),
+            definition,
+            fields,
+            operators: operators.to_owned(),
+        })
+    }
+
+    pub fn process(&self, ctx: &Context) -> Result<Value> {
+        let values = self
+            .fields
+            .iter()
+            .map(|field| field.process(ctx))
+            .collect::<Result<Vec<_>>>()?;
+        self.score(ctx, values)
+    }
+
+    pub async fn process_async(&self, ctx: &Context) -> Result<Value> {
+        let mut values = Vec::with_capacity(self.fields.len());
+        for field in &self.fields {
+            values.push(field.process_async(ctx).await?);
+        }
+        self.score(ctx, values)
+    }
+
+    fn score(&self, ctx: &Context, values: Vec<Value>) -> Result<Value> {
+        let mut points = Map::new();
+        let mut bins = Vec::new();
+
+        for (characteristic, value) in self.definition.characteristics.iter().zip(values) {
+            let bin = self.bin(characteristic, &value)?.ok_or_else(|| {
+                WorkflowError::ScorecardBinMissing {
+                    component_id: self.id.to_owned(),
+                    characteristic: characteristic.name.to_owned(),
+                    value,
+                }
+            })?;
+
+            points.insert(
+                characteristic.name.to_owned(),
+                Value::Number(bin.points.to_owned()),
+            );
+            bins.push(bin);
+        }
+
+        let score = table::sum(
+            &self
+                .definition
+                .base_points
+                .iter()
+                .chain(bins.iter().map(|bin| &bin.points))
+                .collect::<Vec<_>>(),
+        );
+
+        // Stable, so bins with the same points keep the characteristic order.
+        bins.sort_by(|left, right| {
+            let points = |bin: &ScorecardBin| bin.points.as_f64().unwrap_or_default();
+            points(right).total_cmp(&points(left))
+        });
+        let reason_codes = bins
+            .iter()
+            .filter_map(|bin| bin.reason_code.to_owned())
+            .take(self.definition.max_reason_codes)
+            .collect::<Vec<_>>();
+
+        let output = json!({
+            "score": score,
+            "points": points,
+            "reason_codes": reason_codes,
+        });
+        ctx.set_output(self.id.to_owned(), output.to_owned());
+        Ok(output)
+    }
+
+    pub fn dependencies(&self) -> Vec<String> {
+        let mut dependencies = self
+            .fields
+            .iter()
+            .flat_map(|field| field.dependencies())
+            .collect::<Vec<_>>();
+        dependencies.sort();
+        dependencies.dedup();
+        dependencies
+    }
+
+    fn bin<'a>(
+        &self,
+        characteristic: &'a Characteristic,
+        value: &Value,
+    ) -> Result<Option<&'a ScorecardBin>> {
+        for bin in &characteristic.bins {
+            let mut matched = true;
+            for test in &bin.tests {
+                if !test.eval(value, &self.operators)? {
+                    matched = false;
+                    break;
+                }
+            }
+            if matched {
+                return Ok(Some(bin));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl AsyncComponent for Scorecard {
+    fn dependencies(&self) -> Vec<String> {
+        Scorecard::dependencies(self)
+    }
+
+    fn dependants(&self) -> Vec<String> {
+        vec![]
+    }
+
+    async fn process(&self, ctx: &Context) -> Result<Vec<String>> {
+        self.process_async(ctx).await?;
+        Ok(vec![])
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use ruline_condition::OperatorRegistry;
+use ruline_field::FunctionRegistry;
+use ruline_output::OutputDefinition;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    Component, ComponentDefinition, ErrorPolicy, ForEachDefinition, JoinMode, Schema,
+    VariableDefinition, Workflow, WorkflowError,
+};
+
+/// Everything needed to build a workflow, as stored by a source.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct WorkflowSpec {
+    pub definition: Value,
+    pub output: Value,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub variables: Value,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub schema: Value,
+}
+
+impl WorkflowSpec {
+    /// Rewrites the spec so that equivalent specs serialize identically: keys
+    /// are sorted, dependant lists sorted and deduplicated, and fields equal
+    /// to their default left out.
+    pub fn canonicalize(&self) -> Result<WorkflowSpec> {
+        let variables = match canonical::<HashMap<String, VariableDefinition>>(&self.variables)? {
+            Value::Object(variables) if variables.is_empty() => Value::Null,
+            variables => variables,
+        };
+
+        Ok(WorkflowSpec {
+            definition: canonical_components(&self.definition)?,
+            output: canonical::<OutputDefinition>(&self.output)?,
+            variables,
+            schema: canonical::<Schema>(&self.schema)?,
+        })
+    }
+
+    /// The canonical form of the spec as pretty-printed JSON.
+    pub fn to_canonical_string(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.canonicalize()?)?)
+    }
+}
+
+/// Runs `f` accepting any function and operator name, as a spec is
+/// canonicalized apart from the registries it is built with.
+fn any_name<T>(f: impl FnOnce() -> T) -> T {
+    FunctionRegistry::any_name(|| OperatorRegistry::any_name(f))
+}
+
+fn canonical<T: DeserializeOwned + Serialize>(value: &Value) -> Result<Value> {
+    if value.is_null() {
+        return Ok(Value::Null);
+    }
+
+    let parsed: T =
+        any_name(|| serde_json::from_value(value.to_owned())).map_err(WorkflowError::Serde)?;
+    Ok(serde_json::to_value(parsed)?)
+}
+
+fn canonical_components(value: &Value) -> Result<Value> {
+    if value.is_null() {
+        return Ok(Value::Null);
+    }
+
+    let mut components: HashMap<String, ComponentDefinition> =
+        any_name(|| serde_json::from_value(value.to_owned())).map_err(WorkflowError::Serde)?;
+    for component in components.values_mut() {
+        component.normalize()?;
+    }
+    Ok(serde_json::to_value(components)?)
+}
+
+impl ComponentDefinition {
+    /// Drops the error policy and join mode when they are the defaults and
+    /// normalizes the nested definitions.
+    pub fn normalize(&mut self) -> Result<()> {
+        let (on_error, join) = match self {
+            ComponentDefinition::Condition { on_error, join, .. }
+            | ComponentDefinition::Action { on_error, join, .. }
+            | ComponentDefinition::Workflow { on_error, join, .. }
+            | ComponentDefinition::ForEach { on_error, join, .. }
+            | ComponentDefinition::DecisionTable { on_error, join, .. }
+            | ComponentDefinition::Scorecard { on_error, join, .. } => (on_error, join),
+        };
+
+        match on_error {
+            Some(ErrorPolicy::Fail) => *on_error = None,
+            Some(ErrorPolicy::Route { dependants }) => {
+                dependants.sort();
+                dependants.dedup();
+            }
+            _ => {}
+        }
+        if *join == Some(JoinMode::All) {
+            *join = None;
+        }
+
+        match self {
+            ComponentDefinition::Condition { definition, .. } => definition.normalize(),
+            ComponentDefinition::ForEach { definition, .. } => {
+                definition.components = canonical_components(&definition.components)?;
+                definition.output = canonical::<OutputDefinition>(&definition.output)?;
+            }
+            ComponentDefinition::Action { .. }
+            | ComponentDefinition::Workflow { .. }
+            | ComponentDefinition::DecisionTable { .. }
+            | ComponentDefinition::Scorecard { .. } => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl Workflow {
+    /// Turns the workflow back into a spec, in canonical form. Components
+    /// registered with `with_component` live in code rather than in the
+    /// definition and are left out.
+    pub fn to_definition(&self) -> Result<WorkflowSpec> {
+        let mut components = HashMap::new();
+        for step in self.plan.steps() {
+            let name = step.name.to_owned();
+            let on_error = step.policy.to_owned();
+            let join = Some(step.join);
+
+            let component = match &step.component {
+                Component::Condition(condition) => ComponentDefinition::Condition {
+                    name,
+                    definition: condition.definition().to_owned(),
+                    on_error,
+                    join,
+                },
+                Component::Action(action) => ComponentDefinition::Action {
+                    name,
+                    definition: action.definition.to_owned(),
+                    on_error,
+                    join,
+                },
+                Component::Workflow(workflow) => ComponentDefinition::Workflow {
+                    name,
+                    definition: workflow.definition.to_owned(),
+                    on_error,
+                    join,
+                },
+                Component::ForEach(for_each) => {
+                    let inner = for_each.workflow.to_definition()?;
+                    ComponentDefinition::ForEach {
+                        name,
+                        definition: ForEachDefinition {
+                            items: for_each.definition.items.to_owned(),
+                            components: inner.definition,
+                            output: inner.output,
+                        },
+                        on_error,
+                        join,
+                    }
+                }
+                Component::DecisionTable(table) => ComponentDefinition::DecisionTable {
+                    name,
+                    definition: table.definition.to_owned(),
+                    on_error,
+                    join,
+                },
+                Component::Scorecard(scorecard) => ComponentDefinition::Scorecard {
+                    name,
+                    definition: scorecard.definition.to_owned(),
+                    on_error,
+                    join,
+                },
+                Component::Async(_) => continue,
+            };
+            components.insert(step.id.to_owned(), component);
+        }
+
+        WorkflowSpec {
+            definition: serde_json::to_value(components)?,
+            output: serde_json::to_value(&self.output.definition)?,
+            variables: serde_json::to_value(&self.variables)?,
+            schema: serde_json::to_value(&self.schema)?,
+        }
+        .canonicalize()
+    }
+}
@@ -0,0 +1,126 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use ruline_context::Context;
+use ruline_field::{Field, FieldDefinition, FunctionRegistry};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{limits::Budget, Workflow};
+
+/// Looks up the workflows invoked by sub-workflow components.
+pub trait WorkflowResolver: Send + Sync {
+    fn resolve(&self, workflow_id: &str, version: Option<&str>) -> Result<Arc<Workflow>>;
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SubWorkflowDefinition {
+    pub workflow_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub input: HashMap<String, FieldDefinition>,
+}
+
+/// Runs another workflow with an input mapped from the current context and
+/// publishes its output under the component id.
+pub struct SubWorkflow {
+    pub id: String,
+    pub definition: SubWorkflowDefinition,
+    input: Vec<(String, Field)>,
+    dependencies: Vec<String>,
+    resolver: Arc<dyn WorkflowResolver>,
+}
+
+impl Debug for SubWorkflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubWorkflow")
+            .field("id", &self.id)
+            .field("definition", &self.definition)
+            .finish()
+    }
+}
+
+impl SubWorkflow {
+    pub fn new(
+        id: &str,
+        definition: SubWorkflowDefinition,
+        resolver: Arc<dyn WorkflowResolver>,
+        functions: &FunctionRegistry,
+    ) -> Self {
+        let input = definition
+            .input
+            .iter()
+            .map(|(key, value)| (key.to_owned(), Field::with_functions(value, functions)))
+            .collect::<Vec<_>>();
+
+        let mut dependencies = input
+            .iter()
+            .flat_map(|(_, field)| field.dependencies())
+            .collect::<Vec<_>>();
+        dependencies.sort();
+        dependencies.dedup();
+
+        Self {
+            id: id.to_owned(),
+            definition,
+            input,
+            dependencies,
+            resolver,
+        }
+    }
+
+    pub fn resolve(&self) -> Result<Arc<Workflow>> {
+        self.resolver.resolve(
+            &self.definition.workflow_id,
+            self.definition.version.as_deref(),
+        )
+    }
+
+    /// Runs the workflow resolved for the execution one level deeper than
+    /// `budget`, charging its components to it.
+    pub(crate) fn run(&self, ctx: &Context, budget: &Budget) -> Result<Value> {
+        let budget = budget.nested(&self.definition.workflow_id)?;
+        let output = budget
+            .resolve(self)?
+            .process_nested(self.input(ctx)?, &budget)?;
+        ctx.set_output(self.id.to_owned(), output.to_owned());
+        Ok(output)
+    }
+
+    pub(crate) async fn run_async(&self, ctx: &Context, budget: &Budget) -> Result<Value> {
+        let budget = budget.nested(&self.definition.workflow_id)?;
+        let output = budget
+            .resolve(self)?
+            .process_nested_async(self.input_async(ctx).await?, &budget)
+            .await?;
+        ctx.set_output(self.id.to_owned(), output.to_owned());
+        Ok(output)
+    }
+
+    pub fn dependencies(&self) -> Vec<String> {
+        self.dependencies.to_owned()
+    }
+
+    fn input(&self, ctx: &Context) -> Result<Value> {
+        let mut input = Map::with_capacity(self.input.len());
+
+        for (key, field) in &self.input {
+            input.insert(key.to_owned(), field.process(ctx)?);
+        }
+        Ok(Value::Object(input))
+    }
+
+    async fn input_async(&self, ctx: &Context) -> Result<Value> {
+        let mut input = Map::with_capacity(self.input.len());
+
+        for (key, field) in &self.input {
+            input.insert(key.to_owned(), field.process_async(ctx).await?);
+        }
+        Ok(Value::Object(input))
+    }
+}
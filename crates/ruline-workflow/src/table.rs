@@ -0,0 +1,580 @@
+use std::cmp::Ordering;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use ruline_condition::{ComparisonOperator, OperatorRegistry};
+use ruline_context::Context;
+use ruline_field::{Field, FieldDefinition, FunctionRegistry};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+use crate::{AsyncComponent, WorkflowError};
+
+/// Input combinations tried at most when looking for inputs no rule matches.
+const MAX_GAP_CANDIDATES: usize = 10_000;
+
+/// How the outputs of the matching rules make up the table output.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HitPolicy {
+    /// At most one rule may match, overlapping rules are rejected.
+    #[default]
+    Unique,
+    /// The first matching rule in definition order.
+    First,
+    /// The matching rule whose outputs rank highest in the output values.
+    Priority,
+    /// The outputs of every matching rule, in definition order.
+    Collect,
+    CollectSum,
+    CollectMin,
+    CollectMax,
+    CollectCount,
+}
+
+impl HitPolicy {
+    /// Whether the table outputs the row of a single rule, and so needs one
+    /// to match every input.
+    pub(crate) fn single(&self) -> bool {
+        matches!(
+            self,
+            HitPolicy::Unique | HitPolicy::First | HitPolicy::Priority
+        )
+    }
+
+    fn aggregates(&self) -> bool {
+        matches!(
+            self,
+            HitPolicy::CollectSum | HitPolicy::CollectMin | HitPolicy::CollectMax
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TableInput {
+    pub name: String,
+    pub field: FieldDefinition,
+    /// Values the input can take, used when checking for missing rules.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TableOutput {
+    pub name: String,
+    /// Values the output can take, highest priority first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<Value>,
+}
+
+/// Test applied to an input, with the input as first operand and `value`, if
+/// any, as second.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TableTest {
+    pub operator: ComparisonOperator,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
+
+impl TableTest {
+    pub(crate) fn eval(&self, input: &Value, operators: &OperatorRegistry) -> Result<bool> {
+        match &self.value {
+            Some(value) => operators.eval(&self.operator, &[input.to_owned(), value.to_owned()]),
+            None => operators.eval(&self.operator, std::slice::from_ref(input)),
+        }
+    }
+
+    /// Describes the mismatch when a value is given to an operator taking a
+    /// single operand or missing for one taking two.
+    pub(crate) fn arity_error(&self, operators: &OperatorRegistry) -> Option<String> {
+        let takes = |operands: usize| match &self.operator {
+            ComparisonOperator::Custom(_) => operators.validate(&self.operator, operands).is_ok(),
+            ComparisonOperator::Exists
+            | ComparisonOperator::NotExists
+            | ComparisonOperator::Empty
+            | ComparisonOperator::NotEmpty => operands == 1,
+            _ => operands == 2,
+        };
+        match (self.value.is_some(), takes(1), takes(2)) {
+            (true, _, true) | (false, true, _) => None,
+            (true, true, false) => Some(format!("test `{}` does not take a value", self.operator)),
+            (false, false, true) => Some(format!("test `{}` requires a value", self.operator)),
+            (value, false, false) => operators
+                .validate(&self.operator, 1 + usize::from(value))
+                .err()
+                .map(|err| format!("test `{}` is invalid: {}", self.operator, err)),
+        }
+    }
+}
+
+/// A row of the table: one list of tests per input, all of which must hold
+/// for the rule to match (an empty list matches anything), and one value per
+/// output.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TableRule {
+    pub tests: Vec<Vec<TableTest>>,
+    pub outputs: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DecisionTableDefinition {
+    #[serde(default)]
+    pub hit_policy: HitPolicy,
+    pub inputs: Vec<TableInput>,
+    pub outputs: Vec<TableOutput>,
+    pub rules: Vec<TableRule>,
+}
+
+/// Matches its inputs against the rules of the table and publishes the
+/// outputs selected by the hit policy under the component id: an object per
+/// rule keyed by output name, an array of them when collecting, or a number
+/// when aggregating. Rules are numbered from 1.
+#[derive(Debug)]
+pub struct DecisionTable {
+    pub id: String,
+    pub definition: DecisionTableDefinition,
+    inputs: Vec<Field>,
+    operators: OperatorRegistry,
+}
+
+impl DecisionTable {
+    pub fn new(
+        id: &str,
+        definition: DecisionTableDefinition,
+        functions: &FunctionRegistry,
+        operators: &OperatorRegistry,
+    ) -> Result<Self, WorkflowError> {
+        let invalid = |message: String| WorkflowError::DecisionTableInvalid {
+            component_id: id.to_owned(),
+            message,
+        };
+
+        if definition.outputs.is_empty() {
+            return Err(invalid("Table has no outputs".to_owned()));
+        }
+        let policy = definition.hit_policy;
+        if policy.aggregates() && definition.outputs.len() != 1 {
+            return Err(invalid(
+                "Aggregating hit policies require a single output".to_owned(),
+            ));
+        }
+
+        for (index, rule) in definition.rules.iter().enumerate() {
+            let number = index + 1;
+            if rule.tests.len() != definition.inputs.len() {
+                return Err(invalid(format!(
+                    "Rule {} has {} tests but the table has {} inputs",
+                    number,
+                    rule.tests.len(),
+                    definition.inputs.len()
+                )));
+            }
+            if rule.outputs.len() != definition.outputs.len() {
+                return Err(invalid(format!(
+                    "Rule {} has {} outputs but the table has {} outputs",
+                    number,
+                    rule.outputs.len(),
+                    definition.outputs.len()
+                )));
+            }
+
+            let arity_error = rule
+                .tests
+                .iter()
+                .flatten()
+                .find_map(|test| test.arity_error(operators));
+            if let Some(message) = arity_error {
+                return Err(invalid(format!("Rule {} {}", number, message)));
+            }
+
+            if policy.aggregates() && !rule.outputs[0].is_number() {
+                return Err(invalid(format!("Rule {} output must be a number", number)));
+            }
+            if policy == HitPolicy::Priority {
+                let unranked = definition
+                    .outputs
+                    .iter()
+                    .zip(&rule.outputs)
+                    .find(|(output, value)| !output.values.contains(value));
+                if let Some((output, value)) = unranked {
+                    return Err(invalid(format!(
+                        "Rule {} output `{}` is not one of the values of `{}`",
+                        number, value, output.name
+                    )));
+                }
+            }
+        }
+
+        let inputs = definition
+            .inputs
+            .iter()
+            .map(|input| Field::with_functions(&input.field, functions))
+            .collect();
+
+        Ok(Self {
+            id: id.to_owned(),
+            definition,
+            inputs,
+            operators: operators.to_owned(),
+        })
+    }
+
+    pub fn process(&self, ctx: &Context) -> Result<Value> {
+        let (_, output) = self.evaluate(ctx)?;
+        Ok(output)
+    }
+
+    /// Returns the numbers of the matching rules along with the output, which
+    /// is also published.
+    pub fn evaluate(&self, ctx: &Context) -> Result<(Vec<usize>, Value)> {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|field| field.process(ctx))
+            .collect::<Result<Vec<_>>>()?;
+        self.decide(ctx, inputs)
+    }
+
+    pub async fn process_async(&self, ctx: &Context) -> Result<Value> {
+        let mut inputs = Vec::with_capacity(self.inputs.len());
+        for field in &self.inputs {
+            inputs.push(field.process_async(ctx).await?);
+        }
+        let (_, output) = self.decide(ctx, inputs)?;
+        Ok(output)
+    }
+
+    fn decide(&self, ctx: &Context, inputs: Vec<Value>) -> Result<(Vec<usize>, Value)> {
+        let mut matched = Vec::new();
+        for (index, rule) in self.definition.rules.iter().enumerate() {
+            if self.matches(rule, &inputs)? {
+                matched.push(index);
+            }
+        }
+
+        let (matched, output) = self.hit(matched)?;
+        ctx.set_output(self.id.to_owned(), output.to_owned());
+        Ok((matched.into_iter().map(|index| index + 1).collect(), output))
+    }
+
+    pub fn dependencies(&self) -> Vec<String> {
+        let mut dependencies = self
+            .inputs
+            .iter()
+            .flat_map(|field| field.dependencies())
+            .collect::<Vec<_>>();
+        dependencies.sort();
+        dependencies.dedup();
+        dependencies
+    }
+
+    fn matches(&self, rule: &TableRule, inputs: &[Value]) -> Result<bool> {
+        for (tests, input) in rule.tests.iter().zip(inputs) {
+            for test in tests {
+                if !test.eval(input, &self.operators)? {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Applies the hit policy to the indexes of the matching rules, returning
+    /// the ones it keeps and the output.
+    fn hit(&self, matched: Vec<usize>) -> Result<(Vec<usize>, Value)> {
+        let rules = &self.definition.rules;
+        let kept = match self.definition.hit_policy {
+            HitPolicy::Unique if matched.len() > 1 => {
+                return Err(WorkflowError::DecisionTableNotUnique {
+                    component_id: self.id.to_owned(),
+                    rules: matched.iter().map(|index| index + 1).collect(),
+                }
+                .into())
+            }
+            HitPolicy::Unique | HitPolicy::First => matched.into_iter().take(1).collect(),
+            HitPolicy::Priority => matched
+                .into_iter()
+                .min_by_key(|index| self.rank(&rules[*index]))
+                .into_iter()
+                .collect(),
+            _ => matched,
+        };
+
+        let row = |index: &usize| {
+            let row = self
+                .definition
+                .outputs
+                .iter()
+                .zip(&rules[*index].outputs)
+                .map(|(output, value)| (output.name.to_owned(), value.to_owned()))
+                .collect::<Map<_, _>>();
+            Value::Object(row)
+        };
+        let numbers = kept
+            .iter()
+            .filter_map(|index| rules[*index].outputs[0].as_number())
+            .collect::<Vec<_>>();
+
+        let output = match self.definition.hit_policy {
+            HitPolicy::Unique | HitPolicy::First | HitPolicy::Priority => {
+                kept.first().map(row).unwrap_or_default()
+            }
+            HitPolicy::Collect => Value::Array(kept.iter().map(row).collect()),
+            HitPolicy::CollectCount => Value::from(kept.len()),
+            HitPolicy::CollectSum if numbers.is_empty() => Value::Null,
+            HitPolicy::CollectSum => sum(&numbers),
+            HitPolicy::CollectMin => extreme(&numbers, Ordering::Less),
+            HitPolicy::CollectMax => extreme(&numbers, Ordering::Greater),
+        };
+
+        Ok((kept, output))
+    }
+
+    /// Position of each output of `rule` in the values of its column.
+    fn rank(&self, rule: &TableRule) -> Vec<usize> {
+        self.definition
+            .outputs
+            .iter()
+            .zip(&rule.outputs)
+            .map(|(output, value)| {
+                output
+                    .values
+                    .iter()
+                    .position(|candidate| candidate == value)
+                    .unwrap_or(output.values.len())
+            })
+            .collect()
+    }
+
+    /// Pairs of rules, numbered from 1, that some input matches both of, with
+    /// such an input keyed by input name.
+    pub fn overlaps(&self) -> Vec<(usize, usize, Value)> {
+        let witnesses = self.witnesses();
+        let rules = &self.definition.rules;
+        let mut overlaps = Vec::new();
+
+        for (left, left_rule) in rules.iter().enumerate() {
+            for (right, right_rule) in rules.iter().enumerate().skip(left + 1) {
+                let shared = witnesses
+                    .iter()
+                    .enumerate()
+                    .map(|(column, candidates)| {
+                        candidates.iter().find(|candidate| {
+                            self.satisfies(&left_rule.tests[column], candidate)
+                                && self.satisfies(&right_rule.tests[column], candidate)
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>();
+
+                if let Some(shared) = shared {
+                    overlaps.push((left + 1, right + 1, self.named(shared)));
+                }
+            }
+        }
+
+        overlaps
+    }
+
+    /// An input, keyed by input name, that no rule matches. Inputs are drawn
+    /// from the declared values of each input or, failing that, from values
+    /// around the ones the tests compare with.
+    pub fn gap(&self) -> Option<Value> {
+        let witnesses = self.witnesses();
+        let mut positions = vec![0; witnesses.len()];
+
+        for _ in 0..MAX_GAP_CANDIDATES {
+            let inputs = witnesses
+                .iter()
+                .zip(&positions)
+                .map(|(candidates, position)| &candidates[*position])
+                .collect::<Vec<_>>();
+            let covered = self.definition.rules.iter().any(|rule| {
+                rule.tests
+                    .iter()
+                    .zip(&inputs)
+                    .all(|(tests, input)| self.satisfies(tests, input))
+            });
+            if !covered {
+                return Some(self.named(inputs));
+            }
+
+            let next =
+                positions
+                    .iter_mut()
+                    .zip(&witnesses)
+                    .rev()
+                    .find_map(|(position, candidates)| {
+                        *position += 1;
+                        if *position < candidates.len() {
+                            return Some(());
+                        }
+                        *position = 0;
+                        None
+                    });
+            next?;
+        }
+
+        None
+    }
+
+    fn satisfies(&self, tests: &[TableTest], input: &Value) -> bool {
+        tests
+            .iter()
+            .all(|test| test.eval(input, &self.operators).unwrap_or(false))
+    }
+
+    fn named(&self, inputs: Vec<&Value>) -> Value {
+        let named = self
+            .definition
+            .inputs
+            .iter()
+            .zip(inputs)
+            .map(|(input, value)| (input.name.to_owned(), value.to_owned()))
+            .collect::<Map<_, _>>();
+        Value::Object(named)
+    }
+
+    /// Candidate values for each input, enough to tell apart the tests of
+    /// every rule on that input.
+    fn witnesses(&self) -> Vec<Vec<Value>> {
+        self.definition
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(column, input)| {
+                if !input.values.is_empty() {
+                    return input.values.to_owned();
+                }
+
+                let tests = self
+                    .definition
+                    .rules
+                    .iter()
+                    .flat_map(|rule| &rule.tests[column])
+                    .collect::<Vec<_>>();
+                candidates(&tests)
+            })
+            .collect()
+    }
+}
+
+fn candidates(tests: &[&TableTest]) -> Vec<Value> {
+    let mut numbers = Vec::new();
+    let mut others = Vec::new();
+    let mut unary = false;
+
+    for test in tests {
+        let constants = match &test.value {
+            None => {
+                unary = true;
+                continue;
+            }
+            Some(Value::Array(items))
+                if matches!(
+                    test.operator,
+                    ComparisonOperator::Contains | ComparisonOperator::NotContains
+                ) =>
+            {
+                items.iter()
+            }
+            Some(value) => std::slice::from_ref(value).iter(),
+        };
+        for constant in constants {
+            match constant.as_f64() {
+                Some(number) => numbers.push(number),
+                None => others.push(constant.to_owned()),
+            }
+        }
+    }
+
+    numbers.sort_by(f64::total_cmp);
+    numbers.dedup();
+    let mut candidates = numbers
+        .iter()
+        .copied()
+        .chain(numbers.windows(2).map(|pair| (pair[0] + pair[1]) / 2.0))
+        .chain(numbers.first().map(|first| first - 1.0))
+        .chain(numbers.last().map(|last| last + 1.0))
+        .map(number)
+        .collect::<Vec<_>>();
+
+    if others.iter().any(Value::is_string) {
+        let mut fresh = "?".to_owned();
+        while others.contains(&Value::from(fresh.as_str())) {
+            fresh.push('?');
+        }
+        others.push(Value::from(fresh));
+    }
+    if unary {
+        others.extend([Value::Null, Value::from("")]);
+    }
+
+    for other in others {
+        if !candidates.contains(&other) {
+            candidates.push(other);
+        }
+    }
+    if candidates.is_empty() {
+        candidates.push(Value::Null);
+    }
+    candidates
+}
+
+/// The number as an integer when it has no fractional part, so candidates
+/// compare equal to the integer constants they derive from.
+pub(crate) fn number(value: f64) -> Value {
+    match value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+        true => Value::from(value as i64),
+        false => Number::from_f64(value).map_or(Value::Null, Value::Number),
+    }
+}
+
+pub(crate) fn sum(numbers: &[&Number]) -> Value {
+    let integers = numbers
+        .iter()
+        .map(|number| number.as_i64())
+        .collect::<Option<Vec<_>>>();
+    if let Some(total) = integers.and_then(|integers| {
+        integers
+            .into_iter()
+            .try_fold(0i64, |total, integer| total.checked_add(integer))
+    }) {
+        return Value::from(total);
+    }
+
+    let total = numbers.iter().filter_map(|number| number.as_f64()).sum();
+    number(total)
+}
+
+fn extreme(numbers: &[&Number], ordering: Ordering) -> Value {
+    numbers
+        .iter()
+        .copied()
+        .reduce(|best, number| {
+            let compared = number
+                .as_f64()
+                .unwrap_or_default()
+                .total_cmp(&best.as_f64().unwrap_or_default());
+            match compared == ordering {
+                true => number,
+                false => best,
+            }
+        })
+        .map_or(Value::Null, |number| Value::Number(number.to_owned()))
+}
+
+#[async_trait]
+impl AsyncComponent for DecisionTable {
+    fn dependencies(&self) -> Vec<String> {
+        DecisionTable::dependencies(self)
+    }
+
+    fn dependants(&self) -> Vec<String> {
+        vec![]
+    }
+
+    async fn process(&self, ctx: &Context) -> Result<Vec<String>> {
+        self.process_async(ctx).await?;
+        Ok(vec![])
+    }
+}
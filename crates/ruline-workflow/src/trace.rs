@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use ruline_condition::ConditionTrace;
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct Trace {
+    pub steps: Vec<TraceStep>,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TraceStep {
+    pub component_id: String,
+    pub name: String,
+    pub outcome: TraceOutcome,
+    pub skipped: Vec<String>,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum TraceOutcome {
+    Condition(ConditionTrace),
+    Action { variable: String, value: Value },
+    Workflow { workflow_id: String, output: Value },
+    ForEach { output: Value },
+    DecisionTable { rules: Vec<usize>, output: Value },
+    Scorecard { output: Value },
+    Error { message: String },
+}
@@ -0,0 +1,244 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use ruline_action::ActionDefinition;
+use ruline_field::{FieldType, FunctionRegistry, Signature, TypeEnv};
+use serde::Serialize;
+
+use crate::{Component, Schema, SchemaType, VariableDefinition, VariableType, Workflow};
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct TypeMismatch {
+    pub component_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expression_id: Option<String>,
+    pub message: String,
+}
+
+impl Display for TypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.expression_id {
+            Some(expression_id) => write!(
+                f,
+                "component `{}` expression `{}`: {}",
+                self.component_id, expression_id, self.message
+            ),
+            None => write!(f, "component `{}`: {}", self.component_id, self.message),
+        }
+    }
+}
+
+impl From<SchemaType> for FieldType {
+    fn from(schema_type: SchemaType) -> Self {
+        match schema_type {
+            SchemaType::String => FieldType::String,
+            SchemaType::Number | SchemaType::Integer => FieldType::Number,
+            SchemaType::Boolean => FieldType::Boolean,
+            SchemaType::Array => FieldType::Array,
+            SchemaType::Object => FieldType::Object,
+            SchemaType::Null => FieldType::Null,
+        }
+    }
+}
+
+impl From<VariableType> for FieldType {
+    fn from(variable_type: VariableType) -> Self {
+        match variable_type {
+            VariableType::String => FieldType::String,
+            VariableType::Number => FieldType::Number,
+            VariableType::Boolean => FieldType::Boolean,
+            VariableType::Array => FieldType::Array,
+            VariableType::Object => FieldType::Object,
+            VariableType::Any => FieldType::Any,
+        }
+    }
+}
+
+/// Static types known before execution: data paths resolve through the input
+/// schema, variables through their declaration or, failing that, through the
+/// values every `set_variable` action assigns them.
+struct Environment<'a> {
+    schema: Option<&'a Schema>,
+    variables: HashMap<String, FieldType>,
+    functions: &'a FunctionRegistry,
+}
+
+impl<'a> Environment<'a> {
+    fn new(workflow: &'a Workflow) -> Self {
+        let mut env = Environment {
+            schema: workflow.schema.as_ref(),
+            variables: declared(&workflow.variables),
+            functions: &workflow.functions,
+        };
+
+        let mut inferred: HashMap<String, FieldType> = HashMap::new();
+        for step in workflow.plan.steps() {
+            let Component::Action(action) = &step.component else {
+                continue;
+            };
+            let ActionDefinition::SetVariable { variable, value } = &action.definition;
+            if env.variables.contains_key(variable) {
+                continue;
+            }
+
+            let value_type = value.infer_type(&env).unwrap_or(FieldType::Any);
+            inferred
+                .entry(variable.to_owned())
+                .and_modify(|field_type| {
+                    if *field_type != value_type {
+                        *field_type = FieldType::Any;
+                    }
+                })
+                .or_insert(value_type);
+        }
+
+        env.variables.extend(inferred);
+        env
+    }
+}
+
+fn declared(variables: &HashMap<String, VariableDefinition>) -> HashMap<String, FieldType> {
+    variables
+        .iter()
+        .map(|(name, variable)| (name.to_owned(), variable.variable_type.into()))
+        .collect()
+}
+
+impl TypeEnv for Environment<'_> {
+    fn data_type(&self, path: &str) -> FieldType {
+        let Some(mut schema) = self.schema else {
+            return FieldType::Any;
+        };
+
+        for segment in path.split('/').skip(1) {
+            let segment = segment.replace("~1", "/").replace("~0", "~");
+            let next = match (schema.schema_type, &schema.items) {
+                (Some(SchemaType::Array), Some(items)) => Some(items.as_ref()),
+                _ => schema.properties.get(&segment),
+            };
+            match next {
+                Some(next) => schema = next,
+                None => return FieldType::Any,
+            }
+        }
+
+        schema.schema_type.map_or(FieldType::Any, FieldType::from)
+    }
+
+    fn variable_type(&self, variable: &str) -> FieldType {
+        self.variables
+            .get(variable)
+            .copied()
+            .unwrap_or(FieldType::Any)
+    }
+
+    fn function_signature(&self, name: &str) -> Option<Signature> {
+        self.functions.signature(name)
+    }
+}
+
+impl Workflow {
+    /// Infers the type of every field, function call and comparison in the
+    /// workflow and returns the mismatches found, without running it.
+    pub fn check_types(&self) -> Vec<TypeMismatch> {
+        let env = Environment::new(self);
+        let mut mismatches = Vec::new();
+
+        for step in self.plan.steps() {
+            match &step.component {
+                Component::Condition(condition) => {
+                    mismatches.extend(condition.check_types(&env).into_iter().map(
+                        |(expression_id, err)| TypeMismatch {
+                            component_id: step.id.to_owned(),
+                            expression_id: Some(expression_id),
+                            message: err.to_string(),
+                        },
+                    ));
+                }
+                Component::Action(action) => {
+                    let ActionDefinition::SetVariable { variable, value } = &action.definition;
+                    let message = match value.infer_type(&env) {
+                        Err(err) => err.to_string(),
+                        Ok(value_type) => {
+                            let expected = env.variable_type(variable);
+                            if value_type.is(expected) || expected.is(value_type) {
+                                continue;
+                            }
+                            format!(
+                                "Variable `{}` of type `{}` cannot be set to a value of type `{}`",
+                                variable, expected, value_type
+                            )
+                        }
+                    };
+                    mismatches.push(TypeMismatch {
+                        component_id: step.id.to_owned(),
+                        expression_id: None,
+                        message,
+                    });
+                }
+                Component::Workflow(workflow) => {
+                    let mut input = workflow.definition.input.iter().collect::<Vec<_>>();
+                    input.sort_by_key(|(key, _)| *key);
+                    for (key, value) in input {
+                        if let Err(err) = value.infer_type(&env) {
+                            mismatches.push(TypeMismatch {
+                                component_id: step.id.to_owned(),
+                                expression_id: None,
+                                message: format!("Input `{}`: {}", key, err),
+                            });
+                        }
+                    }
+                }
+                Component::ForEach(for_each) => {
+                    if let Err(err) = for_each.definition.items.infer_type(&env) {
+                        mismatches.push(TypeMismatch {
+                            component_id: step.id.to_owned(),
+                            expression_id: None,
+                            message: format!("Items: {}", err),
+                        });
+                    }
+                    mismatches.extend(for_each.workflow.check_types().into_iter().map(
+                        |mismatch| TypeMismatch {
+                            component_id: format!("{}/{}", step.id, mismatch.component_id),
+                            ..mismatch
+                        },
+                    ));
+                }
+                Component::DecisionTable(table) => {
+                    for input in &table.definition.inputs {
+                        if let Err(err) = input.field.infer_type(&env) {
+                            mismatches.push(TypeMismatch {
+                                component_id: step.id.to_owned(),
+                                expression_id: None,
+                                message: format!("Input `{}`: {}", input.name, err),
+                            });
+                        }
+                    }
+                }
+                Component::Scorecard(scorecard) => {
+                    for characteristic in &scorecard.definition.characteristics {
+                        if let Err(err) = characteristic.field.infer_type(&env) {
+                            mismatches.push(TypeMismatch {
+                                component_id: step.id.to_owned(),
+                                expression_id: None,
+                                message: format!(
+                                    "Characteristic `{}`: {}",
+                                    characteristic.name, err
+                                ),
+                            });
+                        }
+                    }
+                }
+                Component::Async(_) => {}
+            }
+        }
+
+        mismatches.sort_by(|left, right| {
+            (&left.component_id, &left.expression_id)
+                .cmp(&(&right.component_id, &right.expression_id))
+        });
+        mismatches
+    }
+}
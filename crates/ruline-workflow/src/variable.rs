@@ -0,0 +1,50 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VariableType {
+    String,
+    Number,
+    Boolean,
+    Array,
+    Object,
+    Any,
+}
+
+impl VariableType {
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            VariableType::String => value.is_string(),
+            VariableType::Number => value.is_number(),
+            VariableType::Boolean => value.is_boolean(),
+            VariableType::Array => value.is_array(),
+            VariableType::Object => value.is_object(),
+            VariableType::Any => true,
+        }
+    }
+}
+
+impl Display for VariableType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            VariableType::String => "string",
+            VariableType::Number => "number",
+            VariableType::Boolean => "boolean",
+            VariableType::Array => "array",
+            VariableType::Object => "object",
+            VariableType::Any => "any",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct VariableDefinition {
+    #[serde(rename = "type")]
+    pub variable_type: VariableType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
@@ -0,0 +1,105 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use insta::assert_snapshot;
+use ruline_workflow::Workflow;
+use serde_json::{json, Value};
+
+fn workflow() -> Workflow {
+    Workflow::builder()
+        .with_definition(json!({
+            "1": {
+                "type": "condition",
+                "name": "is_adult",
+                "definition": {
+                    "type": "binary",
+                    "fallbacks": [ "3" ],
+                    "results": [ "2" ],
+                    "expression": {
+                        "id": "100",
+                        "type": "comparison",
+                        "operator": "greater_than_or_equal",
+                        "operands": [
+                        {
+                            "type": "data",
+                            "path": "/age"
+                        },
+                        {
+                            "type": "value",
+                            "value": 18
+                        }
+                        ]
+                    }
+                }
+            },
+            "2": {
+                "type": "action",
+                "name": "approve",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "decision",
+                    "value": {
+                        "type": "value",
+                        "value": "approved"
+                    }
+                }
+            },
+            "3": {
+                "type": "action",
+                "name": "reject",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "decision",
+                    "value": {
+                        "type": "value",
+                        "value": "rejected"
+                    }
+                }
+            }
+        }))
+        .with_output(json!({
+            "decision": {
+                "type": "variable",
+                "variable": "decision"
+            }
+        }))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_process_batch() {
+    let workflow = workflow();
+    let inputs = (0..200)
+        .map(|age| json!({ "age": age % 40 }))
+        .collect::<Vec<_>>();
+
+    let results = workflow.process_batch(inputs.to_owned());
+    assert_eq!(results.len(), inputs.len());
+    for (input, result) in inputs.into_iter().zip(results) {
+        assert_eq!(result.unwrap(), workflow.process(input).unwrap());
+    }
+}
+
+#[test]
+fn test_process_batch_errors() {
+    let workflow = workflow();
+    let inputs = vec![json!({ "age": 30 }), json!({}), json!({ "age": 12 })];
+
+    let results = workflow.process_batch_with_workers(inputs, 2);
+    assert_eq!(
+        results[0].as_ref().unwrap(),
+        &json!({ "decision": "approved" })
+    );
+    assert_snapshot!(results[1].as_ref().unwrap_err().to_string());
+    assert_eq!(
+        results[2].as_ref().unwrap(),
+        &json!({ "decision": "rejected" })
+    );
+}
+
+#[test]
+fn test_process_batch_empty() {
+    assert!(workflow().process_batch(Vec::<Value>::new()).is_empty());
+}
@@ -0,0 +1,132 @@
+use insta::assert_snapshot;
+use ruline_workflow::Workflow;
+use serde_json::json;
+
+fn workflow() -> Workflow {
+    Workflow::builder()
+        .with_definition(json!({
+            "1": {
+                "type": "condition",
+                "name": "segment",
+                "definition": {
+                    "type": "decision",
+                    "fallbacks": [ "4" ],
+                    "results": {
+                        "100": [ "2" ],
+                        "101": [ "3" ]
+                    },
+                    "expressions": [
+                    {
+                        "id": "100",
+                        "type": "comparison",
+                        "operator": "greater_than",
+                        "operands": [
+                        { "type": "data", "path": "/amount" },
+                        { "type": "value", "value": 1000 }
+                        ]
+                    },
+                    {
+                        "id": "101",
+                        "type": "comparison",
+                        "operator": "equals",
+                        "operands": [
+                        { "type": "data", "path": "/country" },
+                        { "type": "value", "value": "AR" }
+                        ]
+                    }
+                    ]
+                }
+            },
+            "2": {
+                "type": "action",
+                "name": "set_rate",
+                "on_error": { "type": "route", "dependants": [ "4" ] },
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "rate",
+                    "value": {
+                        "type": "function",
+                        "function": "div",
+                        "args": [
+                        { "type": "data", "path": "/amount" },
+                        { "type": "value", "value": 100 }
+                        ]
+                    }
+                }
+            },
+            "3": {
+                "type": "action",
+                "name": "set_local",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "local",
+                    "value": { "type": "value", "value": true }
+                }
+            },
+            "4": {
+                "type": "action",
+                "name": "set_default",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "rate",
+                    "value": { "type": "value", "value": 1 }
+                }
+            },
+            "5": {
+                "type": "for_each",
+                "name": "price_items",
+                "definition": {
+                    "items": { "type": "data", "path": "/items" },
+                    "components": {},
+                    "output": {}
+                }
+            },
+            "6": {
+                "type": "action",
+                "name": "set_items",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "items",
+                    "value": { "type": "output", "output_id": "5", "path": "" }
+                }
+            }
+        }))
+        .with_output(json!({}))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_export_dot() {
+    assert_snapshot!(workflow().to_dot());
+}
+
+#[test]
+fn test_export_mermaid() {
+    assert_snapshot!(workflow().to_mermaid());
+}
+
+#[test]
+fn test_export_natural_order() {
+    let set = |value: usize| {
+        json!({
+            "type": "action",
+            "name": format!("set_{}", value),
+            "definition": {
+                "type": "set_variable",
+                "variable": "value",
+                "value": { "type": "value", "value": value }
+            }
+        })
+    };
+    let workflow = Workflow::builder()
+        .with_definition(json!({ "10": set(10), "2": set(2), "1": set(1) }))
+        .with_output(json!({}))
+        .build()
+        .unwrap();
+
+    let mermaid = workflow.to_mermaid();
+    let position = |name: &str| mermaid.find(name).unwrap();
+    assert!(position("set_1") < position("set_2"));
+    assert!(position("set_2") < position("set_10"));
+}
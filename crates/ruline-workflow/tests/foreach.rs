@@ -0,0 +1,166 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use insta::assert_snapshot;
+use ruline_workflow::Workflow;
+use serde_json::json;
+
+fn workflow() -> Workflow {
+    let definition = json!({
+        "1": {
+            "type": "for_each",
+            "name": "price_items",
+            "definition": {
+                "items": {
+                    "type": "data",
+                    "path": "/items"
+                },
+                "components": {
+                    "1": {
+                        "type": "condition",
+                        "name": "is_bulk",
+                        "definition": {
+                            "type": "binary",
+                            "fallbacks": [ "3" ],
+                            "results": [ "2" ],
+                            "expression": {
+                                "id": "100",
+                                "type": "comparison",
+                                "operator": "greater_than_or_equal",
+                                "operands": [
+                                {
+                                    "type": "item",
+                                    "path": "/quantity"
+                                },
+                                {
+                                    "type": "data",
+                                    "path": "/bulk_quantity"
+                                }
+                                ]
+                            }
+                        }
+                    },
+                    "2": {
+                        "type": "action",
+                        "name": "set_bulk_total",
+                        "definition": {
+                            "type": "set_variable",
+                            "variable": "total",
+                            "value": {
+                                "type": "function",
+                                "function": "mul",
+                                "args": [
+                                { "type": "item", "path": "/price" },
+                                { "type": "item", "path": "/quantity" },
+                                { "type": "value", "value": 0.5 }
+                                ]
+                            }
+                        }
+                    },
+                    "3": {
+                        "type": "action",
+                        "name": "set_total",
+                        "definition": {
+                            "type": "set_variable",
+                            "variable": "total",
+                            "value": {
+                                "type": "function",
+                                "function": "mul",
+                                "args": [
+                                { "type": "item", "path": "/price" },
+                                { "type": "item", "path": "/quantity" }
+                                ]
+                            }
+                        }
+                    }
+                },
+                "output": {
+                    "line": { "type": "index" },
+                    "sku": { "type": "item", "path": "/sku" },
+                    "total": { "type": "variable", "variable": "total" }
+                }
+            }
+        },
+        "2": {
+            "type": "action",
+            "name": "set_first_total",
+            "definition": {
+                "type": "set_variable",
+                "variable": "first_total",
+                "value": {
+                    "type": "output",
+                    "output_id": "1",
+                    "path": "/0/total"
+                }
+            }
+        }
+    });
+
+    Workflow::builder()
+        .with_definition(definition)
+        .with_output(json!({
+            "items": {
+                "type": "output",
+                "output_id": "1",
+                "path": ""
+            },
+            "first_total": {
+                "type": "variable",
+                "variable": "first_total"
+            }
+        }))
+        .build()
+        .unwrap()
+}
+
+fn data() -> serde_json::Value {
+    json!({
+        "bulk_quantity": 10,
+        "items": [
+            { "sku": "A", "price": 2, "quantity": 20 },
+            { "sku": "B", "price": 5, "quantity": 1 }
+        ]
+    })
+}
+
+fn expected() -> serde_json::Value {
+    json!({
+        "items": [
+            { "line": 0, "sku": "A", "total": 20.0 },
+            { "line": 1, "sku": "B", "total": 5.0 }
+        ],
+        "first_total": 20.0
+    })
+}
+
+#[test]
+fn test_for_each() {
+    let workflow = workflow();
+
+    workflow.validate().unwrap();
+    assert_eq!(workflow.process(data()).unwrap(), expected());
+    assert_eq!(workflow.process_parallel(data()).unwrap(), expected());
+}
+
+#[tokio::test]
+async fn test_for_each_async() {
+    assert_eq!(workflow().process_async(data()).await.unwrap(), expected());
+}
+
+#[test]
+fn test_for_each_empty() {
+    let result = workflow().process(json!({ "bulk_quantity": 10, "items": [] }));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "`/0/total` in output `1` not found"
+    );
+}
+
+#[test]
+fn test_for_each_items_invalid() {
+    assert_snapshot!(workflow()
+        .process(json!({ "items": "A" }))
+        .unwrap_err()
+        .to_string());
+}
@@ -0,0 +1,147 @@
+#![cfg(any(feature = "yaml", feature = "toml"))]
+
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use insta::assert_snapshot;
+use ruline_workflow::Workflow;
+use serde_json::json;
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml() {
+    let workflow = Workflow::builder()
+        .with_definition_yaml(
+            r#"
+"1":
+  type: condition
+  name: is_adult
+  definition:
+    type: binary
+    results: ["2"]
+    fallbacks: []
+    expression:
+      id: "100"
+      type: comparison
+      operator: greater_than_or_equal
+      operands:
+        - { type: data, path: /age }
+        - { type: value, value: 18 }
+"2":
+  type: action
+  name: approve
+  definition:
+    type: set_variable
+    variable: decision
+    value: { type: value, value: approved }
+"#,
+        )
+        .unwrap()
+        .with_output_yaml("decision: { type: variable, variable: decision }")
+        .unwrap()
+        .with_variables(json!({ "decision": { "type": "string", "value": "rejected" } }))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        workflow.process(json!({ "age": 20 })).unwrap(),
+        json!({ "decision": "approved" })
+    );
+    assert_eq!(
+        workflow.process(json!({ "age": 10 })).unwrap(),
+        json!({ "decision": "rejected" })
+    );
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_errors() {
+    let err = Workflow::builder()
+        .with_definition_yaml(
+            r#"
+"1":
+  type: action
+  name: approve
+  definition:
+    type: set_variable
+    variable: decision
+    value: { type: constant, value: approved }
+"#,
+        )
+        .err()
+        .unwrap();
+    assert_snapshot!("yaml_unknown_variant", err.to_string());
+
+    let err = Workflow::builder()
+        .with_output_yaml("decision:\n  type: variable\n  variable: [decision")
+        .err()
+        .unwrap();
+    assert_snapshot!("yaml_syntax", err.to_string());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml() {
+    let workflow = Workflow::builder()
+        .with_definition_toml(
+            r#"
+[1]
+type = "condition"
+name = "is_adult"
+
+[1.definition]
+type = "binary"
+results = ["2"]
+fallbacks = []
+
+[1.definition.expression]
+id = "100"
+type = "comparison"
+operator = "greater_than_or_equal"
+operands = [
+  { type = "data", path = "/age" },
+  { type = "value", value = 18 },
+]
+
+[2]
+type = "action"
+name = "approve"
+definition = { type = "set_variable", variable = "decision", value = { type = "value", value = "approved" } }
+"#,
+        )
+        .unwrap()
+        .with_output_toml(r#"decision = { type = "variable", variable = "decision" }"#)
+        .unwrap()
+        .with_variables(json!({ "decision": { "type": "string", "value": "rejected" } }))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        workflow.process(json!({ "age": 20 })).unwrap(),
+        json!({ "decision": "approved" })
+    );
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml_errors() {
+    let err = Workflow::builder()
+        .with_definition_toml(
+            r#"
+[1]
+type = "action"
+name = "approve"
+definition = { type = "set_variable", variable = "decision" }
+"#,
+        )
+        .err()
+        .unwrap();
+    assert_snapshot!("toml_missing_field", err.to_string());
+
+    let err = Workflow::builder()
+        .with_output_toml("decision = { type = \"variable\",\n  variable = }")
+        .err()
+        .unwrap();
+    assert_snapshot!("toml_syntax", err.to_string());
+}
@@ -0,0 +1,172 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use ruline_field::{AsyncFunction, CustomFunction, FieldType, FunctionRegistry, Signature};
+use ruline_workflow::{TypeMismatch, Workflow};
+use serde_json::{json, Value};
+
+#[derive(Debug)]
+struct Discount;
+
+impl CustomFunction for Discount {
+    fn signature(&self) -> Signature {
+        Signature::new(vec![FieldType::String], FieldType::Number)
+    }
+
+    fn call(&self, args: Vec<Value>) -> Result<Value> {
+        Ok(match args[0].as_str() {
+            Some("gold") => json!(0.2),
+            _ => json!(0),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct RemoteDiscount;
+
+#[async_trait]
+impl AsyncFunction for RemoteDiscount {
+    fn signature(&self) -> Signature {
+        Discount.signature()
+    }
+
+    async fn call(&self, args: Vec<Value>) -> Result<Value> {
+        tokio::task::yield_now().await;
+        Discount.call(args)
+    }
+}
+
+fn functions() -> FunctionRegistry {
+    FunctionRegistry::default()
+        .register("discount", Discount)
+        .unwrap()
+}
+
+fn definition(tier: Value) -> Value {
+    json!({
+        "1": {
+            "type": "condition",
+            "name": "has_discount",
+            "definition": {
+                "type": "binary",
+                "fallbacks": [],
+                "results": [ "2" ],
+                "expression": {
+                    "id": "100",
+                    "type": "comparison",
+                    "operator": "greater_than",
+                    "operands": [
+                        { "type": "function", "function": "discount", "args": [ tier.to_owned() ] },
+                        { "type": "value", "value": 0 }
+                    ]
+                }
+            }
+        },
+        "2": {
+            "type": "action",
+            "name": "set_discount",
+            "definition": {
+                "type": "set_variable",
+                "variable": "discount",
+                "value": { "type": "function", "function": "discount", "args": [ tier ] }
+            }
+        }
+    })
+}
+
+fn output() -> Value {
+    json!({
+        "discount": { "type": "variable", "variable": "discount" },
+        "bronze": {
+            "type": "function",
+            "function": "discount",
+            "args": [ { "type": "value", "value": "bronze" } ]
+        }
+    })
+}
+
+#[test]
+fn test_functions_process() {
+    let workflow = Workflow::builder()
+        .with_definition(definition(json!({ "type": "data", "path": "/tier" })))
+        .with_output(output())
+        .with_variables(json!({ "discount": { "type": "number", "value": 0 } }))
+        .with_functions(functions())
+        .build()
+        .unwrap();
+
+    let output = workflow.process(json!({ "tier": "gold" })).unwrap();
+    assert_eq!(output, json!({ "discount": 0.2, "bronze": 0 }));
+
+    let output = workflow.process(json!({ "tier": "silver" })).unwrap();
+    assert_eq!(output, json!({ "discount": 0, "bronze": 0 }));
+}
+
+#[test]
+fn test_functions_not_registered() {
+    let err = Workflow::builder()
+        .with_definition(definition(json!({ "type": "data", "path": "/tier" })))
+        .with_output(output())
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown variant `discount`, expected one of `add`, `sub`, `mul`, `div`, `mod`, `pow`, `min`, `max`, `abs`, `mean`, `median`, `upper`, `lower`, `join`"
+    );
+}
+
+#[test]
+fn test_functions_check_types() {
+    let workflow = Workflow::builder()
+        .with_definition(definition(json!({ "type": "value", "value": 3 })))
+        .with_output(output())
+        .with_functions(functions())
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        workflow.check_types(),
+        vec![
+            TypeMismatch {
+                component_id: "1".to_owned(),
+                expression_id: Some("100".to_owned()),
+                message: "`discount` called with (`number`): Argument type invalid".to_owned(),
+            },
+            TypeMismatch {
+                component_id: "2".to_owned(),
+                expression_id: None,
+                message: "`discount` called with (`number`): Argument type invalid".to_owned(),
+            }
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_functions_process_async() {
+    let workflow = Workflow::builder()
+        .with_definition(definition(json!({ "type": "data", "path": "/tier" })))
+        .with_output(output())
+        .with_variables(json!({ "discount": { "type": "number", "value": 0 } }))
+        .with_functions(
+            FunctionRegistry::default()
+                .register_async("discount", RemoteDiscount)
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+
+    let output = workflow
+        .process_async(json!({ "tier": "gold" }))
+        .await
+        .unwrap();
+    assert_eq!(output, json!({ "discount": 0.2, "bronze": 0 }));
+
+    let err = workflow.process(json!({ "tier": "gold" })).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Function `discount` is async and can only be called from async processing"
+    );
+}
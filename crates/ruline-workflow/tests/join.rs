@@ -0,0 +1,155 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use std::sync::Arc;
+
+use ruline_workflow::Workflow;
+use serde_json::{json, Value};
+
+fn condition(path: &str, results: &[&str], fallbacks: &[&str]) -> Value {
+    json!({
+        "type": "condition",
+        "name": format!("check_{}", path.trim_start_matches('/')),
+        "definition": {
+            "type": "binary",
+            "fallbacks": fallbacks,
+            "results": results,
+            "expression": {
+                "id": "100",
+                "type": "comparison",
+                "operator": "equals",
+                "operands": [
+                { "type": "data", "path": path },
+                { "type": "value", "value": true }
+                ]
+            }
+        }
+    })
+}
+
+fn join(join: Option<&str>) -> Value {
+    let mut component = json!({
+        "type": "action",
+        "name": "join",
+        "definition": {
+            "type": "set_variable",
+            "variable": "joined",
+            "value": { "type": "value", "value": true }
+        }
+    });
+    if let Some(join) = join {
+        component["join"] = json!(join);
+    }
+    component
+}
+
+fn build(definition: Value) -> Workflow {
+    Workflow::builder()
+        .with_definition(definition)
+        .with_output(json!({
+            "joined": { "type": "variable", "variable": "joined" }
+        }))
+        .with_variables(json!({
+            "joined": { "type": "boolean", "value": false }
+        }))
+        .build()
+        .unwrap()
+}
+
+/// Runs `data` through every execution strategy, which must agree, and
+/// returns whether the join component ran.
+async fn joined(workflow: Workflow, data: Value) -> bool {
+    let output = workflow.process(data.to_owned()).unwrap();
+    assert_eq!(
+        workflow.process_async(data.to_owned()).await.unwrap(),
+        output
+    );
+    assert_eq!(workflow.process_parallel(data.to_owned()).unwrap(), output);
+    assert_eq!(
+        Arc::new(workflow)
+            .process_parallel_async(data)
+            .await
+            .unwrap(),
+        output
+    );
+
+    output["joined"].as_bool().unwrap()
+}
+
+/// `1` branches to either `2` or `3`, which both lead to `4`.
+fn diamond(mode: Option<&str>) -> Workflow {
+    build(json!({
+        "1": condition("/branch", &["2"], &["3"]),
+        "2": condition("/left", &["4"], &[]),
+        "3": condition("/right", &["4"], &[]),
+        "4": join(mode)
+    }))
+}
+
+/// `1` and `2` both run and each decide on their own whether `3` runs.
+fn converging(mode: Option<&str>) -> Workflow {
+    build(json!({
+        "1": condition("/left", &["3"], &[]),
+        "2": condition("/right", &["3"], &[]),
+        "3": join(mode)
+    }))
+}
+
+#[tokio::test]
+async fn test_join_diamond() {
+    for mode in [None, Some("all"), Some("any")] {
+        let data = json!({ "branch": true, "left": true, "right": false });
+        assert!(joined(diamond(mode), data).await);
+
+        let data = json!({ "branch": false, "left": false, "right": true });
+        assert!(joined(diamond(mode), data).await);
+
+        let data = json!({ "branch": true, "left": false, "right": true });
+        assert!(!joined(diamond(mode), data).await);
+    }
+}
+
+#[tokio::test]
+async fn test_join_all() {
+    for mode in [None, Some("all")] {
+        let data = json!({ "left": true, "right": true });
+        assert!(joined(converging(mode), data).await);
+
+        let data = json!({ "left": true, "right": false });
+        assert!(!joined(converging(mode), data).await);
+
+        let data = json!({ "left": false, "right": true });
+        assert!(!joined(converging(mode), data).await);
+    }
+}
+
+#[tokio::test]
+async fn test_join_any() {
+    let data = json!({ "left": true, "right": true });
+    assert!(joined(converging(Some("any")), data).await);
+
+    let data = json!({ "left": true, "right": false });
+    assert!(joined(converging(Some("any")), data).await);
+
+    let data = json!({ "left": false, "right": true });
+    assert!(joined(converging(Some("any")), data).await);
+
+    let data = json!({ "left": false, "right": false });
+    assert!(!joined(converging(Some("any")), data).await);
+}
+
+#[test]
+fn test_join_trace_skipped() {
+    let (_, trace) = converging(None)
+        .process_with_trace(json!({ "left": false, "right": true }))
+        .unwrap();
+
+    let skipped = trace
+        .steps
+        .iter()
+        .flat_map(|step| step.skipped.to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(trace.steps.len(), 2);
+    assert_eq!(skipped, vec!["3"]);
+}
@@ -0,0 +1,267 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use insta::assert_snapshot;
+use ruline_context::Context;
+use ruline_workflow::{AsyncComponent, Limits, Workflow};
+use serde_json::{json, Value};
+
+fn copy(id: usize, path: &str) -> (String, Value) {
+    (
+        id.to_string(),
+        json!({
+            "type": "action",
+            "name": format!("copy_{}", id),
+            "definition": {
+                "type": "set_variable",
+                "variable": format!("value_{}", id),
+                "value": {
+                    "type": "data",
+                    "path": path
+                }
+            }
+        }),
+    )
+}
+
+fn workflow(components: usize, limits: Limits) -> Result<Workflow> {
+    let definition = (1..=components)
+        .map(|id| copy(id, "/values"))
+        .collect::<serde_json::Map<_, _>>();
+
+    Workflow::builder()
+        .with_definition(Value::Object(definition))
+        .with_output(json!({
+            "values": {
+                "type": "variable",
+                "variable": "value_1"
+            }
+        }))
+        .with_limits(limits)
+        .build()
+}
+
+fn nested(depth: usize) -> Value {
+    (0..depth).fold(json!({ "type": "value", "value": 1 }), |arg, _| {
+        json!({
+            "type": "function",
+            "function": "abs",
+            "args": [ arg ]
+        })
+    })
+}
+
+#[test]
+fn test_limits_unset() {
+    let workflow = workflow(3, Limits::default()).unwrap();
+    assert_eq!(
+        workflow.process(json!({ "values": [1, 2, 3] })).unwrap(),
+        json!({ "values": [1, 2, 3] })
+    );
+}
+
+#[test]
+fn test_limits_components() {
+    let limits = Limits {
+        max_components: Some(2),
+        ..Default::default()
+    };
+
+    workflow(2, limits.to_owned())
+        .unwrap()
+        .process(json!({ "values": [] }))
+        .unwrap();
+    assert_snapshot!(workflow(3, limits)
+        .unwrap()
+        .process_parallel(json!({ "values": [] }))
+        .unwrap_err()
+        .to_string());
+}
+
+#[test]
+fn test_limits_function_depth() {
+    let limits = Limits {
+        max_function_depth: Some(3),
+        ..Default::default()
+    };
+    let build = |depth| {
+        Workflow::builder()
+            .with_definition(json!({}))
+            .with_output(json!({ "value": nested(depth) }))
+            .with_limits(limits.to_owned())
+            .build()
+    };
+
+    build(3).unwrap();
+    assert_snapshot!(build(4).unwrap_err().to_string());
+}
+
+#[test]
+fn test_limits_function_depth_literals() {
+    let limits = Limits {
+        max_function_depth: Some(1),
+        ..Default::default()
+    };
+    let build = |field| {
+        Workflow::builder()
+            .with_definition(json!({
+                "1": {
+                    "type": "decision_table",
+                    "name": "rate",
+                    "definition": {
+                        "inputs": [ { "name": "age", "field": field } ],
+                        "outputs": [ { "name": "rate", "values": [ nested(3) ] } ],
+                        "rules": [
+                            {
+                                "tests": [ [ { "operator": "equals", "value": nested(3) } ] ],
+                                "outputs": [ nested(3) ]
+                            }
+                        ]
+                    }
+                }
+            }))
+            .with_output(json!({
+                "rate": { "type": "value", "value": nested(3) }
+            }))
+            .with_limits(limits.to_owned())
+            .build()
+    };
+
+    build(nested(1)).unwrap();
+    assert_eq!(
+        build(nested(2)).unwrap_err().to_string(),
+        "Function nesting depth 2 in `1` exceeds the limit of 1"
+    );
+}
+
+#[test]
+fn test_limits_array_len() {
+    let limits = Limits {
+        max_array_len: Some(2),
+        ..Default::default()
+    };
+    let workflow = workflow(1, limits).unwrap();
+
+    workflow.process(json!({ "values": [[1, 2], 3] })).unwrap();
+    assert_snapshot!(workflow
+        .process(json!({ "values": [[1, 2, 3]] }))
+        .unwrap_err()
+        .to_string());
+}
+
+#[test]
+fn test_limits_bytes() {
+    let limits = Limits {
+        max_input_bytes: Some(32),
+        max_output_bytes: Some(16),
+        ..Default::default()
+    };
+    let workflow = workflow(1, limits).unwrap();
+
+    workflow.process(json!({ "values": [1] })).unwrap();
+    assert_snapshot!(
+        "limits_input_bytes",
+        workflow
+            .process(json!({ "values": [1], "padding": "0123456789" }))
+            .unwrap_err()
+            .to_string()
+    );
+    assert_snapshot!(
+        "limits_output_bytes",
+        workflow
+            .process(json!({ "values": [1, 2, 3, 4] }))
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[derive(Debug)]
+struct Sleep;
+
+#[async_trait]
+impl AsyncComponent for Sleep {
+    fn dependencies(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn dependants(&self) -> Vec<String> {
+        vec!["1".to_owned()]
+    }
+
+    async fn process(&self, _ctx: &Context) -> Result<Vec<String>> {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        Ok(vec!["1".to_owned()])
+    }
+}
+
+#[tokio::test]
+async fn test_limits_deadline() {
+    let workflow = Workflow::builder()
+        .with_definition(Value::Object([copy(1, "/values")].into_iter().collect()))
+        .with_output(json!({}))
+        .with_component("sleep", Box::new(Sleep))
+        .with_limits(Limits {
+            deadline: Some(Duration::from_millis(5)),
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+
+    assert_snapshot!(workflow
+        .process_async(json!({ "values": [] }))
+        .await
+        .unwrap_err()
+        .to_string());
+}
+
+fn for_each(limits: Limits) -> Workflow {
+    Workflow::builder()
+        .with_definition(json!({
+            "1": {
+                "type": "for_each",
+                "name": "values",
+                "definition": {
+                    "items": { "type": "data", "path": "/values" },
+                    "components": {
+                        "1": copy(1, "/values").1
+                    },
+                    "output": {}
+                }
+            }
+        }))
+        .with_output(json!({}))
+        .with_limits(limits)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_limits_components_for_each() {
+    let limits = Limits {
+        max_components: Some(4),
+        ..Default::default()
+    };
+    let workflow = for_each(limits);
+
+    workflow.process(json!({ "values": [1, 2, 3] })).unwrap();
+    assert_eq!(
+        workflow
+            .process(json!({ "values": [1, 2, 3, 4] }))
+            .unwrap_err()
+            .to_string(),
+        "Processed more than 4 components"
+    );
+    assert_eq!(
+        workflow
+            .process_async(json!({ "values": [1, 2, 3, 4] }))
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Processed more than 4 components"
+    );
+}
@@ -0,0 +1,183 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use insta::assert_snapshot;
+use ruline_workflow::{LintKind, Workflow};
+use serde_json::{json, Value};
+
+fn set_variable(variable: &str, value: Value) -> Value {
+    json!({
+        "type": "action",
+        "name": format!("set_{}", variable),
+        "definition": {
+            "type": "set_variable",
+            "variable": variable,
+            "value": value
+        }
+    })
+}
+
+fn workflow(definition: Value, output: Value) -> Workflow {
+    Workflow::builder()
+        .with_definition(definition)
+        .with_output(output)
+        .with_variables(json!({
+            "threshold": { "type": "number", "value": 18 }
+        }))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_lint_clean() {
+    let workflow = workflow(
+        json!({
+            "1": {
+                "type": "condition",
+                "name": "adult",
+                "definition": {
+                    "type": "binary",
+                    "fallbacks": [],
+                    "results": [ "2" ],
+                    "expression": {
+                        "id": "100",
+                        "type": "comparison",
+                        "operator": "greater_than_or_equal",
+                        "operands": [
+                        { "type": "data", "path": "/age" },
+                        { "type": "variable", "variable": "threshold" }
+                        ]
+                    }
+                }
+            },
+            "2": set_variable("label", json!({ "type": "value", "value": "adult" }))
+        }),
+        json!({
+            "label": { "type": "variable", "variable": "label" }
+        }),
+    );
+
+    assert!(workflow.lint().is_empty());
+}
+
+#[test]
+fn test_lint() {
+    let workflow = workflow(
+        json!({
+            "1": {
+                "type": "condition",
+                "name": "segment",
+                "definition": {
+                    "type": "decision",
+                    "fallbacks": [ "4" ],
+                    "results": {
+                        "100": [ "2" ],
+                        "999": [ "3" ]
+                    },
+                    "expressions": [
+                    {
+                        "id": "100",
+                        "type": "logical",
+                        "operator": "and",
+                        "expressions": [
+                        {
+                            "id": "101",
+                            "type": "comparison",
+                            "operator": "greater_than",
+                            "operands": [
+                            { "type": "data", "path": "/amount" },
+                            { "type": "variable", "variable": "limit" }
+                            ]
+                        },
+                        {
+                            "id": "101",
+                            "type": "comparison",
+                            "operator": "exists",
+                            "operands": [
+                            { "type": "data", "path": "/country" }
+                            ]
+                        }
+                        ]
+                    }
+                    ]
+                }
+            },
+            "2": set_variable("rate", json!({ "type": "value", "value": 0.1 })),
+            "3": set_variable("fee", json!({ "type": "value", "value": 5 })),
+            "4": set_variable("unused", json!({
+                "type": "function",
+                "function": "add",
+                "args": [
+                { "type": "variable", "variable": "threshold" },
+                { "type": "value", "value": 1 }
+                ]
+            }))
+        }),
+        json!({
+            "rate": { "type": "variable", "variable": "rate" },
+            "fee": { "type": "variable", "variable": "fee" },
+            "total": { "type": "variable", "variable": "total" }
+        }),
+    );
+
+    workflow.validate().unwrap();
+    let warnings = workflow.lint();
+    assert_snapshot!(warnings
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n"));
+
+    let duplicate = warnings
+        .iter()
+        .find(|warning| warning.kind == LintKind::DuplicateExpressionId)
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(duplicate).unwrap(),
+        json!({
+            "kind": "duplicate_expression_id",
+            "component_id": "1",
+            "message": "Expression id `101` is used 2 times"
+        })
+    );
+}
+
+#[test]
+fn test_lint_for_each() {
+    let workflow = workflow(
+        json!({
+            "1": {
+                "type": "for_each",
+                "name": "lines",
+                "definition": {
+                    "items": { "type": "data", "path": "/lines" },
+                    "components": {
+                        "1": set_variable("line", json!({
+                            "type": "function",
+                            "function": "mul",
+                            "args": [
+                            { "type": "item", "path": "/price" },
+                            { "type": "variable", "variable": "rate" }
+                            ]
+                        }))
+                    },
+                    "output": {
+                        "total": { "type": "variable", "variable": "line" }
+                    }
+                }
+            }
+        }),
+        json!({
+            "threshold": { "type": "variable", "variable": "threshold" },
+            "lines": { "type": "output", "output_id": "1", "path": "" }
+        }),
+    );
+
+    assert_snapshot!(workflow
+        .lint()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n"));
+}
@@ -0,0 +1,200 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use anyhow::Result;
+use ruline_condition::{Arity, CustomOperator, OperatorRegistry};
+use ruline_workflow::Workflow;
+use serde_json::{json, Value};
+
+#[derive(Debug)]
+struct Between;
+
+impl CustomOperator for Between {
+    fn arity(&self) -> Arity {
+        Arity::Exact(3)
+    }
+
+    fn eval(&self, operands: &[Value]) -> Result<bool> {
+        match [&operands[0], &operands[1], &operands[2]].map(Value::as_f64) {
+            [Some(value), Some(min), Some(max)] => Ok(min <= value && value <= max),
+            _ => Ok(false),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct StartsWith;
+
+impl CustomOperator for StartsWith {
+    fn arity(&self) -> Arity {
+        Arity::Exact(2)
+    }
+
+    fn eval(&self, operands: &[Value]) -> Result<bool> {
+        match (&operands[0], &operands[1]) {
+            (Value::String(value), Value::String(prefix)) => Ok(value.starts_with(prefix)),
+            _ => Ok(false),
+        }
+    }
+}
+
+fn operators() -> OperatorRegistry {
+    OperatorRegistry::default()
+        .register_negated("between", "outside", Between)
+        .unwrap()
+        .register("starts_with", StartsWith)
+        .unwrap()
+}
+
+fn workflow(operators: OperatorRegistry) -> Result<Workflow> {
+    Workflow::builder()
+        .with_definition(json!({
+            "1": {
+                "type": "for_each",
+                "name": "ages",
+                "definition": {
+                    "items": { "type": "data", "path": "/ages" },
+                    "components": {
+                        "1": {
+                            "type": "condition",
+                            "name": "is_working_age",
+                            "definition": {
+                                "type": "binary",
+                                "fallbacks": [],
+                                "results": [],
+                                "expression": {
+                                    "id": "100",
+                                    "type": "comparison",
+                                    "operator": "outside",
+                                    "operands": [
+                                        { "type": "item", "path": "" },
+                                        { "type": "value", "value": 18 },
+                                        { "type": "value", "value": 65 }
+                                    ]
+                                }
+                            }
+                        }
+                    },
+                    "output": {
+                        "branch": { "type": "output", "output_id": "1", "path": "/branch" }
+                    }
+                }
+            }
+        }))
+        .with_output(json!({
+            "ages": { "type": "output", "output_id": "1", "path": "" }
+        }))
+        .with_operators(operators)
+        .build()
+}
+
+#[test]
+fn test_operators_process() {
+    let workflow = workflow(operators()).unwrap();
+    workflow.validate().unwrap();
+
+    let output = workflow.process(json!({ "ages": [12, 30, 70] })).unwrap();
+    assert_eq!(
+        output,
+        json!({
+            "ages": [
+                { "branch": "results" },
+                { "branch": "fallbacks" },
+                { "branch": "results" }
+            ]
+        })
+    );
+}
+
+#[test]
+fn test_operators_not_registered() {
+    let err = workflow(OperatorRegistry::default()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown variant `outside`, expected one of `equals`, `not_equals`, `greater_than`, `greater_than_or_equal`, `less_than`, `less_than_or_equal`, `contains`, `not_contains`, `exists`, `not_exists`, `empty`, `not_empty`"
+    );
+}
+
+fn table(test: &Value) -> Value {
+    json!({
+        "type": "decision_table",
+        "name": "region",
+        "definition": {
+            "hit_policy": "first",
+            "inputs": [ { "name": "code", "field": { "type": "data", "path": "/code" } } ],
+            "outputs": [ { "name": "region" } ],
+            "rules": [
+                { "tests": [ [ test ] ], "outputs": [ "south" ] },
+                { "tests": [ [] ], "outputs": [ "other" ] }
+            ]
+        }
+    })
+}
+
+fn scorecard(test: &Value) -> Value {
+    json!({
+        "type": "scorecard",
+        "name": "risk",
+        "definition": {
+            "characteristics": [{
+                "name": "code",
+                "field": { "type": "data", "path": "/code" },
+                "bins": [
+                    { "tests": [ test ], "points": 10 },
+                    { "tests": [], "points": 0 }
+                ]
+            }]
+        }
+    })
+}
+
+fn build(definition: Value) -> Result<Workflow> {
+    Workflow::builder()
+        .with_definition(definition)
+        .with_operators(operators())
+        .build()
+}
+
+#[test]
+fn test_operators_table() {
+    let test = json!({ "operator": "starts_with", "value": "AR" });
+    let workflow = Workflow::builder()
+        .with_definition(json!({ "1": table(&test), "2": scorecard(&test) }))
+        .with_output(json!({
+            "region": { "type": "output", "output_id": "1", "path": "/region" },
+            "score": { "type": "output", "output_id": "2", "path": "/score" }
+        }))
+        .with_operators(operators())
+        .build()
+        .unwrap();
+
+    let output = workflow.process(json!({ "code": "AR-123" })).unwrap();
+    assert_eq!(output, json!({ "region": "south", "score": 10 }));
+
+    let output = workflow.process(json!({ "code": "US-123" })).unwrap();
+    assert_eq!(output, json!({ "region": "other", "score": 0 }));
+}
+
+#[test]
+fn test_operators_table_arity() {
+    let unary = json!({ "operator": "starts_with" });
+    let ternary = json!({ "operator": "between", "value": 1 });
+    let errors = [
+        build(json!({ "1": table(&unary) })),
+        build(json!({ "1": table(&ternary) })),
+        build(json!({ "1": scorecard(&unary) })),
+    ]
+    .into_iter()
+    .map(|result| result.unwrap_err().to_string())
+    .collect::<Vec<_>>();
+
+    assert_eq!(
+        errors,
+        vec![
+            "Decision table `1` is invalid: Rule 1 test `starts_with` requires a value",
+            "Decision table `1` is invalid: Rule 1 test `between` is invalid: Expected 3 operands, got 2",
+            "Scorecard `1` is invalid: Bin 1 of characteristic `code` test `starts_with` requires a value",
+        ]
+    );
+}
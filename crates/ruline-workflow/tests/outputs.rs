@@ -0,0 +1,189 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use std::sync::Arc;
+
+use ruline_workflow::Workflow;
+use serde_json::{json, Value};
+
+fn workflow() -> Workflow {
+    Workflow::builder()
+        .with_definition(json!({
+            "1": {
+                "type": "condition",
+                "name": "is_adult",
+                "definition": {
+                    "type": "binary",
+                    "fallbacks": [],
+                    "results": [ "2" ],
+                    "expression": {
+                        "id": "100",
+                        "type": "comparison",
+                        "operator": "greater_than_or_equal",
+                        "operands": [
+                            { "type": "data", "path": "/age" },
+                            { "type": "value", "value": 18 }
+                        ]
+                    }
+                }
+            },
+            "2": {
+                "type": "action",
+                "name": "set_adult_rate",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "rate",
+                    "value": { "type": "value", "value": 0.1 }
+                }
+            },
+            "3": {
+                "type": "action",
+                "name": "set_branch",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "branch",
+                    "value": { "type": "output", "output_id": "1", "path": "/branch" }
+                }
+            },
+            "4": {
+                "type": "condition",
+                "name": "segment",
+                "definition": {
+                    "type": "decision",
+                    "fallbacks": [],
+                    "results": { "200": [], "201": [] },
+                    "expressions": [
+                        {
+                            "id": "200",
+                            "type": "comparison",
+                            "operator": "equals",
+                            "operands": [
+                                { "type": "data", "path": "/country" },
+                                { "type": "value", "value": "AR" }
+                            ]
+                        },
+                        {
+                            "id": "201",
+                            "type": "comparison",
+                            "operator": "exists",
+                            "operands": [ { "type": "data", "path": "/vip" } ]
+                        }
+                    ]
+                }
+            }
+        }))
+        .with_output(json!({
+            "is_adult": { "type": "output", "output_id": "1", "path": "" },
+            "rate": { "type": "variable", "variable": "rate" },
+            "branch": { "type": "output", "output_id": "3", "path": "" },
+            "segment": { "type": "output", "output_id": "4", "path": "/matched" }
+        }))
+        .with_variables(json!({ "rate": { "type": "number", "value": 0.3 } }))
+        .build()
+        .unwrap()
+}
+
+fn expected(adult: bool) -> Value {
+    match adult {
+        true => json!({
+            "is_adult": { "branch": "results", "matched": [ "100" ] },
+            "rate": 0.1,
+            "branch": { "variable": "branch", "value": "results" },
+            "segment": [ "200", "201" ]
+        }),
+        false => json!({
+            "is_adult": { "branch": "fallbacks", "matched": [] },
+            "rate": 0.3,
+            "branch": { "variable": "branch", "value": "fallbacks" },
+            "segment": []
+        }),
+    }
+}
+
+#[test]
+fn test_outputs() {
+    let workflow = workflow();
+
+    assert_eq!(
+        workflow
+            .process(json!({ "age": 30, "country": "AR", "vip": true }))
+            .unwrap(),
+        expected(true)
+    );
+    assert_eq!(
+        workflow
+            .process(json!({ "age": 10, "country": "UY", "vip": null }))
+            .unwrap(),
+        expected(false)
+    );
+}
+
+#[test]
+fn test_outputs_parallel() {
+    let data = json!({ "age": 30, "country": "AR", "vip": true });
+    assert_eq!(workflow().process_parallel(data).unwrap(), expected(true));
+}
+
+#[tokio::test]
+async fn test_outputs_async() {
+    let data = json!({ "age": 10, "country": "UY", "vip": null });
+    assert_eq!(
+        workflow().process_async(data.to_owned()).await.unwrap(),
+        expected(false)
+    );
+    assert_eq!(
+        Arc::new(workflow())
+            .process_parallel_async(data)
+            .await
+            .unwrap(),
+        expected(false)
+    );
+}
+
+#[test]
+fn test_outputs_read_by_components() {
+    let workflow = Workflow::builder()
+        .with_definition(json!({
+            "1": {
+                "type": "action",
+                "name": "set_total",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "total",
+                    "value": {
+                        "type": "function",
+                        "function": "add",
+                        "args": [
+                            { "type": "data", "path": "/a" },
+                            { "type": "data", "path": "/b" }
+                        ]
+                    }
+                }
+            },
+            "2": {
+                "type": "action",
+                "name": "set_double",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "double",
+                    "value": {
+                        "type": "function",
+                        "function": "mul",
+                        "args": [
+                            { "type": "output", "output_id": "1", "path": "/value" },
+                            { "type": "value", "value": 2 }
+                        ]
+                    }
+                }
+            }
+        }))
+        .with_output(json!({ "double": { "type": "variable", "variable": "double" } }))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        workflow.process(json!({ "a": 1, "b": 2 })).unwrap(),
+        json!({ "double": 6.0 })
+    );
+}
@@ -0,0 +1,270 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use insta::assert_snapshot;
+use ruline_context::Context;
+use ruline_workflow::{AsyncComponent, Workflow};
+use serde_json::{json, Map, Value};
+use tokio::sync::Barrier;
+
+fn fan_out(branches: usize) -> (Value, Value) {
+    let mut definition = Map::new();
+    let mut output = Map::new();
+
+    for branch in 0..branches {
+        let condition_id = format!("{}", branch * 3 + 1);
+        let result_id = format!("{}", branch * 3 + 2);
+        let fallback_id = format!("{}", branch * 3 + 3);
+
+        definition.insert(
+            condition_id,
+            json!({
+                "type": "condition",
+                "name": format!("check_{}", branch),
+                "definition": {
+                    "type": "binary",
+                    "fallbacks": [ fallback_id ],
+                    "results": [ result_id ],
+                    "expression": {
+                        "id": "100",
+                        "type": "comparison",
+                        "operator": "greater_than",
+                        "operands": [
+                        {
+                            "type": "data",
+                            "path": "/amount"
+                        },
+                        {
+                            "type": "value",
+                            "value": branch * 10
+                        }
+                        ]
+                    }
+                }
+            }),
+        );
+
+        for (id, tier) in [(result_id, "high"), (fallback_id, "low")] {
+            definition.insert(
+                id,
+                json!({
+                    "type": "action",
+                    "name": format!("set_{}_{}", tier, branch),
+                    "definition": {
+                        "type": "set_variable",
+                        "variable": format!("tier_{}", branch),
+                        "value": {
+                            "type": "value",
+                            "value": tier
+                        }
+                    }
+                }),
+            );
+        }
+
+        output.insert(
+            format!("tier_{}", branch),
+            json!({
+                "type": "variable",
+                "variable": format!("tier_{}", branch)
+            }),
+        );
+    }
+
+    (Value::Object(definition), Value::Object(output))
+}
+
+#[test]
+fn test_process_parallel() {
+    let (definition, output) = fan_out(20);
+    let workflow = Workflow::builder()
+        .with_definition(definition)
+        .with_output(output)
+        .build()
+        .unwrap();
+
+    for amount in [0, 55, 500] {
+        let data = json!({ "amount": amount });
+        assert_eq!(
+            workflow.process_parallel(data.to_owned()).unwrap(),
+            workflow.process(data).unwrap()
+        );
+    }
+
+    let result = workflow.process_parallel(json!({ "amount": 55 })).unwrap();
+    assert_eq!(result["tier_5"], json!("high"));
+    assert_eq!(result["tier_6"], json!("low"));
+}
+
+#[test]
+fn test_process_parallel_skipped_branch() {
+    let definition = json!({
+        "1": {
+            "type": "condition",
+            "name": "is_adult",
+            "definition": {
+                "type": "binary",
+                "fallbacks": [],
+                "results": [ "2" ],
+                "expression": {
+                    "id": "100",
+                    "type": "comparison",
+                    "operator": "greater_than_or_equal",
+                    "operands": [
+                    {
+                        "type": "data",
+                        "path": "/age"
+                    },
+                    {
+                        "type": "value",
+                        "value": 18
+                    }
+                    ]
+                }
+            }
+        },
+        "2": {
+            "type": "condition",
+            "name": "is_senior",
+            "definition": {
+                "type": "binary",
+                "fallbacks": [],
+                "results": [ "3" ],
+                "expression": {
+                    "id": "100",
+                    "type": "comparison",
+                    "operator": "greater_than_or_equal",
+                    "operands": [
+                    {
+                        "type": "data",
+                        "path": "/age"
+                    },
+                    {
+                        "type": "value",
+                        "value": 65
+                    }
+                    ]
+                }
+            }
+        },
+        "3": {
+            "type": "action",
+            "name": "set_discount",
+            "definition": {
+                "type": "set_variable",
+                "variable": "discount",
+                "value": {
+                    "type": "value",
+                    "value": 0.2
+                }
+            }
+        },
+        "4": {
+            "type": "action",
+            "name": "set_name",
+            "definition": {
+                "type": "set_variable",
+                "variable": "name",
+                "value": {
+                    "type": "data",
+                    "path": "/name"
+                }
+            }
+        }
+    });
+
+    let workflow = Workflow::builder()
+        .with_definition(definition)
+        .with_output(json!({
+            "name": {
+                "type": "variable",
+                "variable": "name"
+            }
+        }))
+        .build()
+        .unwrap();
+
+    let result = workflow
+        .process_parallel(json!({ "age": 12, "name": "John" }))
+        .unwrap();
+    assert_eq!(result, json!({ "name": "John" }));
+}
+
+#[test]
+fn test_process_parallel_error() {
+    let (definition, output) = fan_out(4);
+    let workflow = Workflow::builder()
+        .with_definition(definition)
+        .with_output(output)
+        .build()
+        .unwrap();
+
+    assert_snapshot!(workflow
+        .process_parallel(json!({}))
+        .unwrap_err()
+        .to_string());
+}
+
+#[derive(Debug)]
+struct Rendezvous {
+    barrier: Arc<Barrier>,
+    id: String,
+}
+
+#[async_trait]
+impl AsyncComponent for Rendezvous {
+    fn dependencies(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn dependants(&self) -> Vec<String> {
+        vec![]
+    }
+
+    async fn process(&self, ctx: &Context) -> Result<Vec<String>> {
+        self.barrier.wait().await;
+        ctx.set_output(self.id.to_owned(), json!({ "done": true }));
+        Ok(vec![])
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_process_parallel_async() {
+    let barrier = Arc::new(Barrier::new(2));
+    let rendezvous = |id: &str| {
+        Box::new(Rendezvous {
+            barrier: Arc::clone(&barrier),
+            id: id.to_owned(),
+        })
+    };
+
+    let workflow = Workflow::builder()
+        .with_definition(json!({}))
+        .with_output(json!({
+            "first": {
+                "type": "output",
+                "output_id": "1",
+                "path": "/done"
+            },
+            "second": {
+                "type": "output",
+                "output_id": "2",
+                "path": "/done"
+            }
+        }))
+        .with_component("1", rendezvous("1"))
+        .with_component("2", rendezvous("2"))
+        .build()
+        .unwrap();
+
+    let result = Arc::new(workflow)
+        .process_parallel_async(json!({}))
+        .await
+        .unwrap();
+    assert_eq!(result, json!({ "first": true, "second": true }));
+}
@@ -0,0 +1,185 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use insta::assert_snapshot;
+use ruline_workflow::{TraceOutcome, Workflow};
+use serde_json::{json, Value};
+
+fn set_variable(variable: &str, value: Value, on_error: Value) -> Value {
+    let mut component = json!({
+        "type": "action",
+        "name": format!("set_{}", variable),
+        "definition": {
+            "type": "set_variable",
+            "variable": variable,
+            "value": value
+        }
+    });
+    if !on_error.is_null() {
+        component["on_error"] = on_error;
+    }
+    component
+}
+
+fn build(definition: Value, output: Value) -> Workflow {
+    Workflow::builder()
+        .with_definition(definition)
+        .with_output(output)
+        .build()
+        .unwrap()
+}
+
+fn route() -> Workflow {
+    build(
+        json!({
+            "1": set_variable(
+                "rate",
+                json!({ "type": "data", "path": "/rate" }),
+                json!({ "type": "route", "dependants": [ "2" ] }),
+            ),
+            "2": set_variable(
+                "error",
+                json!({ "type": "output", "output_id": "1", "path": "/error/message" }),
+                Value::Null,
+            )
+        }),
+        json!({
+            "error": { "type": "variable", "variable": "error" }
+        }),
+    )
+}
+
+#[test]
+fn test_policy_route() {
+    let workflow = route();
+
+    let expected = json!({ "error": "`/rate` in data not found" });
+    assert_eq!(workflow.process(json!({})).unwrap(), expected);
+    assert_eq!(workflow.process_parallel(json!({})).unwrap(), expected);
+
+    assert_eq!(
+        workflow
+            .process(json!({ "rate": 0.1 }))
+            .unwrap_err()
+            .to_string(),
+        "Variable `error` not found"
+    );
+}
+
+#[test]
+fn test_policy_route_trace() {
+    let (_, trace) = route().process_with_trace(json!({})).unwrap();
+
+    match &trace.steps[0].outcome {
+        TraceOutcome::Error { message } => assert_eq!(message, "`/rate` in data not found"),
+        outcome => panic!("unexpected outcome {:?}", outcome),
+    }
+    assert_eq!(trace.steps[1].component_id, "2");
+}
+
+#[tokio::test]
+async fn test_policy_default() {
+    let workflow = build(
+        json!({
+            "1": set_variable(
+                "rate",
+                json!({ "type": "data", "path": "/rate" }),
+                json!({ "type": "default", "value": 0.2 }),
+            )
+        }),
+        json!({
+            "rate": { "type": "variable", "variable": "rate" }
+        }),
+    );
+
+    assert_eq!(workflow.process(json!({})).unwrap(), json!({ "rate": 0.2 }));
+    assert_eq!(
+        workflow.process_async(json!({})).await.unwrap(),
+        json!({ "rate": 0.2 })
+    );
+    assert_eq!(
+        workflow.process(json!({ "rate": 0.1 })).unwrap(),
+        json!({ "rate": 0.1 })
+    );
+}
+
+#[test]
+fn test_policy_skip() {
+    let workflow = build(
+        json!({
+            "1": {
+                "type": "condition",
+                "name": "is_adult",
+                "on_error": { "type": "skip" },
+                "definition": {
+                    "type": "binary",
+                    "fallbacks": [ "3" ],
+                    "results": [ "2" ],
+                    "expression": {
+                        "id": "100",
+                        "type": "comparison",
+                        "operator": "greater_than_or_equal",
+                        "operands": [
+                        { "type": "data", "path": "/age" },
+                        { "type": "value", "value": 18 }
+                        ]
+                    }
+                }
+            },
+            "2": set_variable("decision", json!({ "type": "value", "value": "approved" }), Value::Null),
+            "3": set_variable("decision", json!({ "type": "value", "value": "rejected" }), Value::Null)
+        }),
+        json!({
+            "error": { "type": "output", "output_id": "1", "path": "/error/message" }
+        }),
+    );
+
+    let (result, trace) = workflow.process_with_trace(json!({})).unwrap();
+    assert_eq!(result, json!({ "error": "`/age` in data not found" }));
+    assert_eq!(trace.steps.len(), 1);
+    assert_eq!(trace.steps[0].skipped, vec!["2", "3"]);
+}
+
+#[test]
+fn test_policy_fail() {
+    let workflow = build(
+        json!({
+            "1": set_variable(
+                "rate",
+                json!({ "type": "data", "path": "/rate" }),
+                json!({ "type": "fail" }),
+            )
+        }),
+        json!({}),
+    );
+
+    assert_snapshot!(workflow.process(json!({})).unwrap_err().to_string());
+}
+
+#[test]
+fn test_policy_condition_default() {
+    let result = Workflow::builder()
+        .with_definition(json!({
+            "1": {
+                "type": "condition",
+                "name": "is_adult",
+                "on_error": { "type": "default", "value": true },
+                "definition": {
+                    "type": "binary",
+                    "fallbacks": [],
+                    "results": [],
+                    "expression": {
+                        "id": "100",
+                        "type": "comparison",
+                        "operator": "exists",
+                        "operands": [{ "type": "data", "path": "/age" }]
+                    }
+                }
+            }
+        }))
+        .with_output(json!({}))
+        .build();
+
+    assert_snapshot!(result.unwrap_err().to_string());
+}
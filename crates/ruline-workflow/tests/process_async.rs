@@ -0,0 +1,188 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use insta::assert_snapshot;
+use ruline_context::Context;
+use ruline_workflow::{AsyncComponent, Workflow};
+use serde_json::{json, Value};
+
+#[derive(Debug)]
+struct Lookup {
+    id: String,
+    scores: Value,
+}
+
+#[async_trait]
+impl AsyncComponent for Lookup {
+    fn dependencies(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn dependants(&self) -> Vec<String> {
+        vec![]
+    }
+
+    async fn process(&self, ctx: &Context) -> Result<Vec<String>> {
+        tokio::task::yield_now().await;
+
+        let name = ctx.get_data("/name").unwrap();
+        let score = self.scores[name.as_str().unwrap()].to_owned();
+        ctx.set_output(self.id.to_owned(), json!({ "score": score }));
+
+        Ok(vec![])
+    }
+}
+
+fn lookup() -> Box<dyn AsyncComponent> {
+    Box::new(Lookup {
+        id: "1".to_owned(),
+        scores: json!({ "John": 720, "Jane": 540 }),
+    })
+}
+
+fn definition() -> Value {
+    json!({
+        "2": {
+            "type": "condition",
+            "name": "check_score",
+            "definition": {
+                "type": "binary",
+                "fallbacks": [ "4" ],
+                "results": [ "3" ],
+                "expression": {
+                    "id": "100",
+                    "type": "comparison",
+                    "operator": "greater_than",
+                    "operands": [
+                    {
+                        "type": "output",
+                        "output_id": "1",
+                        "path": "/score"
+                    },
+                    {
+                        "type": "value",
+                        "value": 600
+                    }
+                    ]
+                }
+            }
+        },
+        "3": {
+            "type": "action",
+            "name": "approve",
+            "definition": {
+                "type": "set_variable",
+                "variable": "decision",
+                "value": {
+                    "type": "value",
+                    "value": "approved"
+                }
+            }
+        },
+        "4": {
+            "type": "action",
+            "name": "reject",
+            "definition": {
+                "type": "set_variable",
+                "variable": "decision",
+                "value": {
+                    "type": "value",
+                    "value": "rejected"
+                }
+            }
+        }
+    })
+}
+
+fn output() -> Value {
+    json!({
+        "decision": {
+            "type": "variable",
+            "variable": "decision"
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_process_async() {
+    let workflow = Workflow::builder()
+        .with_definition(definition())
+        .with_output(output())
+        .with_component("1", lookup())
+        .build()
+        .unwrap();
+
+    assert!(workflow.validate().is_ok());
+
+    let result = workflow
+        .process_async(json!({ "name": "John" }))
+        .await
+        .unwrap();
+    assert_eq!(result, json!({ "decision": "approved" }));
+
+    let result = workflow
+        .process_async(json!({ "name": "Jane" }))
+        .await
+        .unwrap();
+    assert_eq!(result, json!({ "decision": "rejected" }));
+}
+
+#[tokio::test]
+async fn test_process_async_sync_components() {
+    let definition = json!({
+        "1": {
+            "type": "action",
+            "name": "set_name",
+            "definition": {
+                "type": "set_variable",
+                "variable": "decision",
+                "value": {
+                    "type": "data",
+                    "path": "/name"
+                }
+            }
+        }
+    });
+
+    let workflow = Workflow::builder()
+        .with_definition(definition)
+        .with_output(output())
+        .build()
+        .unwrap();
+
+    let data = json!({ "name": "John" });
+    let expected = workflow.process(data.to_owned()).unwrap();
+    let result = workflow.process_async(data).await.unwrap();
+
+    assert_eq!(result, expected);
+    assert_eq!(result, json!({ "decision": "John" }));
+}
+
+#[test]
+fn test_process_async_component_sync() {
+    let workflow = Workflow::builder()
+        .with_definition(definition())
+        .with_output(output())
+        .with_component("1", lookup())
+        .build()
+        .unwrap();
+
+    assert_snapshot!(workflow
+        .process(json!({ "name": "John" }))
+        .unwrap_err()
+        .to_string());
+}
+
+#[test]
+fn test_async_component_duplicate() {
+    assert_snapshot!(Workflow::builder()
+        .with_definition(definition())
+        .with_output(output())
+        .with_component("2", lookup())
+        .build()
+        .unwrap_err()
+        .to_string());
+}
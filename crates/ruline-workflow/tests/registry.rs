@@ -0,0 +1,290 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    thread,
+};
+
+use anyhow::{anyhow, Result};
+use insta::assert_snapshot;
+use ruline_field::{CustomFunction, FieldType, FunctionRegistry, Signature};
+use ruline_workflow::{WorkflowRegistry, WorkflowSource, WorkflowSpec};
+use serde_json::{json, Value};
+
+#[derive(Clone, Default)]
+struct Source {
+    specs: Arc<Mutex<HashMap<(String, String), WorkflowSpec>>>,
+    loads: Arc<AtomicUsize>,
+}
+
+impl Source {
+    fn insert(&self, workflow_id: &str, version: &str, definition: Value, output: Value) {
+        self.specs.lock().unwrap().insert(
+            (workflow_id.to_owned(), version.to_owned()),
+            WorkflowSpec {
+                definition,
+                output,
+                ..Default::default()
+            },
+        );
+    }
+
+    fn constant(&self, workflow_id: &str, version: &str, rate: f64) {
+        self.insert(
+            workflow_id,
+            version,
+            json!({}),
+            json!({ "rate": { "type": "value", "value": rate } }),
+        );
+    }
+}
+
+impl WorkflowSource for Source {
+    fn load(&self, workflow_id: &str, version: &str) -> Result<WorkflowSpec> {
+        self.loads.fetch_add(1, Ordering::SeqCst);
+        self.specs
+            .lock()
+            .unwrap()
+            .get(&(workflow_id.to_owned(), version.to_owned()))
+            .cloned()
+            .ok_or_else(|| anyhow!("Version `{}` of `{}` not found", version, workflow_id))
+    }
+}
+
+fn call(workflow_id: &str, version: Option<&str>) -> Value {
+    json!({
+        "1": {
+            "type": "workflow",
+            "name": format!("call_{}", workflow_id),
+            "definition": {
+                "workflow_id": workflow_id,
+                "version": version,
+                "input": {}
+            }
+        }
+    })
+}
+
+#[test]
+fn test_registry_load() {
+    let source = Source::default();
+    source.constant("pricing", "1", 0.1);
+    let registry = WorkflowRegistry::new(source.to_owned());
+
+    let first = registry.load("pricing", "1").unwrap();
+    let second = registry.load("pricing", "1").unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(source.loads.load(Ordering::SeqCst), 1);
+    assert_eq!(first.process(json!({})).unwrap(), json!({ "rate": 0.1 }));
+
+    let err = registry.load("pricing", "2").unwrap_err();
+    assert_snapshot!(err.to_string());
+}
+
+#[test]
+fn test_registry_activate() {
+    let source = Source::default();
+    source.constant("pricing", "1", 0.1);
+    source.constant("pricing", "2", 0.2);
+    let registry = WorkflowRegistry::new(source);
+
+    let err = registry.get("pricing", None).unwrap_err();
+    assert_snapshot!(err.to_string());
+
+    assert_eq!(registry.activate("pricing", "1").unwrap(), None);
+    let in_flight = registry.get("pricing", None).unwrap();
+
+    assert_eq!(
+        registry.activate("pricing", "2").unwrap(),
+        Some("1".to_owned())
+    );
+    assert_eq!(registry.active_version("pricing"), Some("2".to_owned()));
+    assert_eq!(
+        in_flight.process(json!({})).unwrap(),
+        json!({ "rate": 0.1 })
+    );
+    assert_eq!(
+        registry
+            .get("pricing", None)
+            .unwrap()
+            .process(json!({}))
+            .unwrap(),
+        json!({ "rate": 0.2 })
+    );
+    assert_eq!(
+        registry
+            .get("pricing", Some("1"))
+            .unwrap()
+            .process(json!({}))
+            .unwrap(),
+        json!({ "rate": 0.1 })
+    );
+}
+
+#[test]
+fn test_registry_activate_invalid() {
+    let source = Source::default();
+    source.constant("pricing", "1", 0.1);
+    source.insert(
+        "pricing",
+        "2",
+        call("pricing", Some("2")),
+        json!({ "rate": { "type": "output", "output_id": "1", "path": "/rate" } }),
+    );
+    let registry = WorkflowRegistry::new(source);
+
+    registry.activate("pricing", "1").unwrap();
+    let err = registry.activate("pricing", "2").unwrap_err();
+
+    assert_snapshot!(err.to_string());
+    assert_eq!(registry.active_version("pricing"), Some("1".to_owned()));
+}
+
+#[test]
+fn test_registry_sub_workflow() {
+    let source = Source::default();
+    source.constant("pricing", "1", 0.1);
+    source.constant("pricing", "2", 0.2);
+    for (version, pinned) in [("1", None), ("2", Some("1"))] {
+        source.insert(
+            "checkout",
+            version,
+            call("pricing", pinned),
+            json!({ "rate": { "type": "output", "output_id": "1", "path": "/rate" } }),
+        );
+    }
+    let registry = WorkflowRegistry::new(source);
+    registry.activate("pricing", "1").unwrap();
+    registry.activate("checkout", "1").unwrap();
+
+    let process = |version| {
+        registry
+            .get("checkout", Some(version))
+            .unwrap()
+            .process(json!({}))
+            .unwrap()
+    };
+    assert_eq!(process("1"), json!({ "rate": 0.1 }));
+
+    registry.activate("pricing", "2").unwrap();
+    assert_eq!(process("1"), json!({ "rate": 0.2 }));
+    assert_eq!(process("2"), json!({ "rate": 0.1 }));
+}
+
+static DEPLOYED: OnceLock<WorkflowRegistry> = OnceLock::new();
+
+/// Activates the next `pricing` version when called, as a deploy racing with
+/// a run would.
+#[derive(Debug)]
+struct Deploy;
+
+impl CustomFunction for Deploy {
+    fn signature(&self) -> Signature {
+        Signature::new(vec![], FieldType::Boolean)
+    }
+
+    fn call(&self, _args: Vec<Value>) -> Result<Value> {
+        DEPLOYED.get().unwrap().activate("pricing", "2")?;
+        Ok(json!(true))
+    }
+}
+
+#[test]
+fn test_registry_sub_workflow_pinned_per_run() {
+    let source = Source::default();
+    source.constant("pricing", "1", 0.1);
+    source.constant("pricing", "2", 0.2);
+    source.insert(
+        "checkout",
+        "1",
+        json!({
+            "1": {
+                "type": "condition",
+                "name": "deploy",
+                "definition": {
+                    "type": "binary",
+                    "fallbacks": [ "2" ],
+                    "results": [ "2" ],
+                    "expression": {
+                        "id": "100",
+                        "type": "comparison",
+                        "operator": "equals",
+                        "operands": [
+                            { "type": "function", "function": "deploy", "args": [] },
+                            { "type": "value", "value": true }
+                        ]
+                    }
+                }
+            },
+            "2": call("pricing", None)["1"]
+        }),
+        json!({ "rate": { "type": "output", "output_id": "2", "path": "/rate" } }),
+    );
+    let registry = WorkflowRegistry::builder(source)
+        .with_functions(
+            FunctionRegistry::default()
+                .register("deploy", Deploy)
+                .unwrap(),
+        )
+        .build();
+    DEPLOYED.set(registry.to_owned()).ok().unwrap();
+    registry.activate("pricing", "1").unwrap();
+
+    let checkout = registry.load("checkout", "1").unwrap();
+    assert_eq!(checkout.process(json!({})).unwrap(), json!({ "rate": 0.1 }));
+    assert_eq!(registry.active_version("pricing"), Some("2".to_owned()));
+    assert_eq!(checkout.process(json!({})).unwrap(), json!({ "rate": 0.2 }));
+}
+
+#[test]
+fn test_registry_evict() {
+    let source = Source::default();
+    source.constant("pricing", "1", 0.1);
+    source.constant("pricing", "2", 0.2);
+    let registry = WorkflowRegistry::new(source.to_owned());
+    registry.activate("pricing", "1").unwrap();
+    registry.load("pricing", "2").unwrap();
+
+    let err = registry.evict("pricing", "1").unwrap_err();
+    assert_snapshot!(err.to_string());
+
+    registry.evict("pricing", "2").unwrap();
+    registry.load("pricing", "2").unwrap();
+    assert_eq!(source.loads.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_registry_swap_concurrently() {
+    let source = Source::default();
+    for version in 1..=10 {
+        source.constant("pricing", &version.to_string(), version as f64);
+    }
+    let registry = WorkflowRegistry::new(source);
+    registry.activate("pricing", "1").unwrap();
+
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..100 {
+                    let workflow = registry.get("pricing", None).unwrap();
+                    let rate = workflow.process(json!({})).unwrap()["rate"]
+                        .as_f64()
+                        .unwrap();
+                    assert!((1.0..=10.0).contains(&rate));
+                }
+            });
+        }
+
+        for version in 2..=10 {
+            registry.activate("pricing", &version.to_string()).unwrap();
+        }
+    });
+
+    assert_eq!(registry.active_version("pricing"), Some("10".to_owned()));
+}
@@ -0,0 +1,164 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use insta::assert_snapshot;
+use ruline_workflow::{SchemaType, SchemaViolation, ViolationKind, Workflow, WorkflowError};
+use serde_json::{json, Value};
+
+fn schema() -> Value {
+    json!({
+        "type": "object",
+        "required": [ "name", "age" ],
+        "properties": {
+            "name": {
+                "type": "string",
+                "minLength": 1,
+                "maxLength": 20
+            },
+            "age": {
+                "type": "integer",
+                "minimum": 0,
+                "maximum": 150
+            },
+            "country": {
+                "type": "string",
+                "enum": [ "AR", "UY" ]
+            },
+            "items": {
+                "type": "array",
+                "maxItems": 3,
+                "items": {
+                    "type": "object",
+                    "required": [ "price" ],
+                    "properties": {
+                        "price": {
+                            "type": "number",
+                            "minimum": 0
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn workflow() -> Workflow {
+    let definition = json!({
+        "1": {
+            "type": "action",
+            "name": "set_age",
+            "definition": {
+                "type": "set_variable",
+                "variable": "age",
+                "value": {
+                    "type": "data",
+                    "path": "/age"
+                }
+            }
+        }
+    });
+
+    Workflow::builder()
+        .with_definition(definition)
+        .with_output(json!({
+            "age": {
+                "type": "variable",
+                "variable": "age"
+            }
+        }))
+        .with_schema(schema())
+        .build()
+        .unwrap()
+}
+
+fn violations(workflow: &Workflow, data: Value) -> Vec<SchemaViolation> {
+    let error = workflow.process(data).unwrap_err();
+    match error.downcast::<WorkflowError>().unwrap() {
+        WorkflowError::InputInvalid(violations) => violations,
+        error => panic!("unexpected error {}", error),
+    }
+}
+
+#[test]
+fn test_schema_valid_input() {
+    let workflow = workflow();
+
+    let data = json!({
+        "name": "John",
+        "age": 30,
+        "country": "AR",
+        "items": [ { "price": 10.5 } ],
+    });
+    assert!(workflow.validate_input(&data).is_ok());
+    assert_eq!(workflow.process(data).unwrap(), json!({ "age": 30 }));
+}
+
+#[test]
+fn test_schema_missing_field() {
+    let workflow = workflow();
+
+    assert_eq!(
+        violations(&workflow, json!({ "name": "John" })),
+        vec![SchemaViolation {
+            path: "/age".to_owned(),
+            kind: ViolationKind::Missing,
+        }]
+    );
+}
+
+#[test]
+fn test_schema_violations() {
+    let workflow = workflow();
+
+    let violations = violations(
+        &workflow,
+        json!({
+            "name": "",
+            "age": 30.5,
+            "country": "BR",
+            "items": [ { "price": -1 }, {}, { "price": "free" }, { "price": 1 } ],
+        }),
+    );
+
+    assert_eq!(
+        violations[0],
+        SchemaViolation {
+            path: "/age".to_owned(),
+            kind: ViolationKind::TypeMismatch {
+                expected: SchemaType::Integer,
+            },
+        }
+    );
+    assert_eq!(
+        serde_json::to_value(violations.last()).unwrap(),
+        json!({ "path": "/name", "kind": "too_short", "min_length": 1 })
+    );
+    assert_snapshot!(workflow
+        .process(json!({
+            "name": "",
+            "age": 30.5,
+            "country": "BR",
+            "items": [ { "price": -1 }, {}, { "price": "free" }, { "price": 1 } ],
+        }))
+        .unwrap_err()
+        .to_string());
+}
+
+#[test]
+fn test_schema_invalid_root() {
+    let workflow = workflow();
+
+    assert_snapshot!(workflow.process(json!([])).unwrap_err().to_string());
+}
+
+#[test]
+fn test_schema_malformed() {
+    assert_snapshot!(Workflow::builder()
+        .with_definition(json!({}))
+        .with_output(json!({}))
+        .with_schema(json!({ "type": "decimal" }))
+        .build()
+        .unwrap_err()
+        .to_string());
+}
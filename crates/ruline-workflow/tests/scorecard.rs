@@ -0,0 +1,188 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use insta::assert_snapshot;
+use ruline_workflow::Workflow;
+use serde_json::{json, Value};
+
+fn scorecard() -> Value {
+    json!({
+        "type": "scorecard",
+        "name": "credit_score",
+        "definition": {
+            "base_points": 300,
+            "max_reason_codes": 2,
+            "characteristics": [
+                {
+                    "name": "age",
+                    "field": { "type": "data", "path": "/age" },
+                    "bins": [
+                        {
+                            "tests": [ { "operator": "less_than", "value": 25 } ],
+                            "points": 10,
+                            "reason_code": "A1"
+                        },
+                        {
+                            "tests": [
+                                { "operator": "greater_than_or_equal", "value": 25 },
+                                { "operator": "less_than", "value": 60 }
+                            ],
+                            "points": 40,
+                            "reason_code": "A2"
+                        },
+                        { "tests": [], "points": 25, "reason_code": "A3" }
+                    ]
+                },
+                {
+                    "name": "housing",
+                    "field": { "type": "data", "path": "/housing" },
+                    "bins": [
+                        {
+                            "tests": [ { "operator": "contains", "value": [ "own", "mortgage" ] } ],
+                            "points": 60,
+                            "reason_code": "H1"
+                        },
+                        {
+                            "tests": [ { "operator": "equals", "value": "rent" } ],
+                            "points": 20,
+                            "reason_code": "H2"
+                        }
+                    ]
+                },
+                {
+                    "name": "delinquencies",
+                    "field": { "type": "data", "path": "/delinquencies" },
+                    "bins": [
+                        { "tests": [ { "operator": "equals", "value": 0 } ], "points": 50 },
+                        { "tests": [], "points": -30, "reason_code": "D1" }
+                    ]
+                }
+            ]
+        }
+    })
+}
+
+fn workflow(scorecard: Value) -> Workflow {
+    Workflow::builder()
+        .with_definition(json!({
+            "1": scorecard,
+            "2": {
+                "type": "condition",
+                "name": "is_approved",
+                "definition": {
+                    "type": "binary",
+                    "fallbacks": [],
+                    "results": [ "3" ],
+                    "expression": {
+                        "id": "100",
+                        "type": "comparison",
+                        "operator": "greater_than_or_equal",
+                        "operands": [
+                            { "type": "output", "output_id": "1", "path": "/score" },
+                            { "type": "value", "value": 400 }
+                        ]
+                    }
+                }
+            },
+            "3": {
+                "type": "action",
+                "name": "approve",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "approved",
+                    "value": { "type": "value", "value": true }
+                }
+            }
+        }))
+        .with_output(json!({
+            "scorecard": { "type": "output", "output_id": "1", "path": "" },
+            "approved": { "type": "variable", "variable": "approved" }
+        }))
+        .with_variables(json!({ "approved": { "type": "boolean", "value": false } }))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_scorecard() {
+    let workflow = workflow(scorecard());
+    workflow.validate().unwrap();
+
+    assert_eq!(
+        workflow
+            .process(json!({ "age": 30, "housing": "own", "delinquencies": 0 }))
+            .unwrap(),
+        json!({
+            "approved": true,
+            "scorecard": {
+                "score": 450,
+                "points": { "age": 40, "housing": 60, "delinquencies": 50 },
+                "reason_codes": [ "H1", "A2" ]
+            }
+        })
+    );
+
+    assert_eq!(
+        workflow
+            .process(json!({ "age": 70, "housing": "rent", "delinquencies": 2 }))
+            .unwrap(),
+        json!({
+            "approved": false,
+            "scorecard": {
+                "score": 315,
+                "points": { "age": 25, "housing": 20, "delinquencies": -30 },
+                "reason_codes": [ "A3", "H2" ]
+            }
+        })
+    );
+}
+
+#[test]
+fn test_scorecard_without_reason_codes() {
+    let mut scorecard = scorecard();
+    scorecard["definition"]["max_reason_codes"] = json!(0);
+    scorecard["definition"]["base_points"] = json!(0.5);
+
+    let output = workflow(scorecard)
+        .process(json!({ "age": 20, "housing": "own", "delinquencies": 0 }))
+        .unwrap();
+    assert_eq!(output["scorecard"]["score"], json!(120.5));
+    assert_eq!(output["scorecard"]["reason_codes"], json!([]));
+}
+
+#[test]
+fn test_scorecard_bin_missing() {
+    let err = workflow(scorecard())
+        .process(json!({ "age": 30, "housing": "boat", "delinquencies": 0 }))
+        .unwrap_err();
+    assert_snapshot!("scorecard_bin_missing", err.to_string());
+}
+
+#[test]
+fn test_scorecard_invalid() {
+    let build = |scorecard: Value| {
+        Workflow::builder()
+            .with_definition(json!({ "1": scorecard }))
+            .with_output(json!({}))
+            .build()
+            .unwrap_err()
+            .to_string()
+    };
+
+    let mut invalid = scorecard();
+    invalid["definition"]["characteristics"][1]["bins"] = json!([]);
+    assert_snapshot!("scorecard_invalid_bins", build(invalid));
+
+    let mut invalid = scorecard();
+    invalid["definition"]["characteristics"][0]["bins"][1]["tests"][1] =
+        json!({ "operator": "less_than" });
+    assert_snapshot!("scorecard_invalid_test", build(invalid));
+}
+
+#[test]
+fn test_scorecard_to_definition() {
+    let workflow = workflow(scorecard());
+    let definition = workflow.to_definition().unwrap();
+    assert_eq!(definition.definition["1"], scorecard());
+}
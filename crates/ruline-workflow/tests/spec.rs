@@ -0,0 +1,210 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use insta::assert_snapshot;
+use ruline_workflow::{Workflow, WorkflowSpec};
+use serde_json::{json, Value};
+
+fn spec() -> WorkflowSpec {
+    WorkflowSpec {
+        definition: json!({
+            "1": {
+                "type": "condition",
+                "name": "segment",
+                "on_error": { "type": "fail" },
+                "definition": {
+                    "type": "decision",
+                    "fallbacks": [ "4", "4" ],
+                    "results": {
+                        "101": [ "3", "2" ],
+                        "100": [ "2" ]
+                    },
+                    "expressions": [
+                    {
+                        "id": "100",
+                        "type": "comparison",
+                        "operator": "greater_than",
+                        "operands": [
+                        { "type": "data", "path": "/amount" },
+                        { "type": "value", "value": 1000 }
+                        ]
+                    },
+                    {
+                        "id": "101",
+                        "type": "comparison",
+                        "operator": "equals",
+                        "operands": [
+                        { "type": "data", "path": "/country" },
+                        { "type": "value", "value": "AR" }
+                        ]
+                    }
+                    ]
+                }
+            },
+            "2": {
+                "type": "action",
+                "name": "set_rate",
+                "join": "any",
+                "on_error": { "type": "route", "dependants": [ "4", "4" ] },
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "rate",
+                    "value": {
+                        "type": "function",
+                        "function": "div",
+                        "args": [
+                        { "type": "data", "path": "/amount" },
+                        { "type": "value", "value": 100 }
+                        ]
+                    }
+                }
+            },
+            "3": {
+                "type": "for_each",
+                "name": "lines",
+                "join": "all",
+                "definition": {
+                    "items": { "type": "data", "path": "/lines" },
+                    "components": {
+                        "1": {
+                            "type": "action",
+                            "name": "set_total",
+                            "definition": {
+                                "type": "set_variable",
+                                "variable": "total",
+                                "value": { "type": "item", "path": "/price" }
+                            }
+                        }
+                    },
+                    "output": {
+                        "total": { "type": "variable", "variable": "total" }
+                    }
+                }
+            },
+            "4": {
+                "type": "action",
+                "name": "set_default",
+                "join": "any",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "rate",
+                    "value": { "type": "value", "value": 0 }
+                }
+            }
+        }),
+        output: json!({
+            "rate": { "type": "variable", "variable": "rate" },
+            "lines": { "type": "output", "output_id": "3", "path": "" }
+        }),
+        variables: json!({
+            "rate": { "type": "number", "value": 1 }
+        }),
+        schema: Value::Null,
+    }
+}
+
+fn build(spec: &WorkflowSpec) -> Workflow {
+    Workflow::builder()
+        .with_definition(spec.definition.to_owned())
+        .with_output(spec.output.to_owned())
+        .with_variables(spec.variables.to_owned())
+        .with_schema(spec.schema.to_owned())
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_to_definition() {
+    let workflow = build(&spec());
+    let definition = workflow.to_definition().unwrap();
+
+    assert_eq!(definition, spec().canonicalize().unwrap());
+    assert_eq!(build(&definition).to_definition().unwrap(), definition);
+
+    let data = json!({ "amount": 2000, "country": "AR", "lines": [ { "price": 5 } ] });
+    assert_eq!(
+        build(&definition).process(data.to_owned()).unwrap(),
+        workflow.process(data).unwrap()
+    );
+}
+
+#[test]
+fn test_canonical_string() {
+    let canonical = spec().to_canonical_string().unwrap();
+    assert_snapshot!(canonical);
+
+    let reparsed: WorkflowSpec = serde_json::from_str(&canonical).unwrap();
+    assert_eq!(reparsed.to_canonical_string().unwrap(), canonical);
+}
+
+#[test]
+fn test_canonical_string_invalid() {
+    let spec = WorkflowSpec {
+        definition: json!({ "1": { "type": "script" } }),
+        output: json!({}),
+        ..Default::default()
+    };
+
+    let err = spec.to_canonical_string().unwrap_err();
+    assert!(err.to_string().starts_with("unknown variant `script`"));
+}
+
+#[test]
+fn test_canonical_string_custom_names() {
+    let spec = WorkflowSpec {
+        definition: json!({
+            "1": {
+                "type": "condition",
+                "name": "is_local",
+                "definition": {
+                    "type": "binary",
+                    "fallbacks": [],
+                    "results": [ "2" ],
+                    "expression": {
+                        "id": "100",
+                        "type": "comparison",
+                        "operator": "starts_with",
+                        "operands": [
+                        { "type": "data", "path": "/country" },
+                        { "type": "value", "value": "A" }
+                        ]
+                    }
+                }
+            },
+            "2": {
+                "type": "action",
+                "name": "set_rate",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "rate",
+                    "value": {
+                        "type": "function",
+                        "function": "discount",
+                        "args": [ { "type": "data", "path": "/amount" } ]
+                    }
+                }
+            }
+        }),
+        output: json!({
+            "total": {
+                "type": "function",
+                "function": "discount",
+                "args": [ { "type": "variable", "variable": "rate" } ]
+            }
+        }),
+        ..Default::default()
+    };
+
+    let canonical = spec.canonicalize().unwrap();
+    assert_eq!(
+        canonical.definition["1"]["definition"]["expression"]["operator"],
+        json!("starts_with")
+    );
+    assert_eq!(
+        canonical.definition["2"]["definition"]["value"]["function"],
+        json!("discount")
+    );
+    assert_eq!(canonical.output["total"]["function"], json!("discount"));
+    assert_eq!(canonical.canonicalize().unwrap(), canonical);
+}
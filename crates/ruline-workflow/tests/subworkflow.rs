@@ -0,0 +1,264 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use insta::assert_snapshot;
+use ruline_workflow::{Limits, Workflow, WorkflowResolver};
+use serde_json::{json, Value};
+
+#[derive(Clone, Default)]
+struct Resolver {
+    workflows: HashMap<String, (Value, Value)>,
+}
+
+impl Resolver {
+    fn with(mut self, workflow_id: &str, definition: Value, output: Value) -> Self {
+        self.workflows
+            .insert(workflow_id.to_owned(), (definition, output));
+        self
+    }
+
+    fn build(&self, definition: Value, output: Value) -> Result<Workflow> {
+        Workflow::builder()
+            .with_definition(definition)
+            .with_output(output)
+            .with_resolver(Arc::new(self.to_owned()))
+            .build()
+    }
+}
+
+impl WorkflowResolver for Resolver {
+    fn resolve(&self, workflow_id: &str, _version: Option<&str>) -> Result<Arc<Workflow>> {
+        let (definition, output) = self
+            .workflows
+            .get(workflow_id)
+            .ok_or_else(|| anyhow!("Workflow `{}` not found", workflow_id))?;
+
+        Ok(Arc::new(
+            self.build(definition.to_owned(), output.to_owned())?,
+        ))
+    }
+}
+
+fn call(workflow_id: &str) -> Value {
+    json!({
+        "type": "workflow",
+        "name": format!("call_{}", workflow_id),
+        "definition": {
+            "workflow_id": workflow_id,
+            "input": {
+                "amount": {
+                    "type": "data",
+                    "path": "/amount"
+                }
+            }
+        }
+    })
+}
+
+fn resolver() -> Resolver {
+    Resolver::default().with(
+        "fees",
+        json!({
+            "1": {
+                "type": "action",
+                "name": "set_fee",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "fee",
+                    "value": {
+                        "type": "function",
+                        "function": "mul",
+                        "args": [
+                        {
+                            "type": "data",
+                            "path": "/amount"
+                        },
+                        {
+                            "type": "value",
+                            "value": 0.1
+                        }
+                        ]
+                    }
+                }
+            }
+        }),
+        json!({
+            "fee": {
+                "type": "variable",
+                "variable": "fee"
+            }
+        }),
+    )
+}
+
+fn caller(resolver: &Resolver) -> Workflow {
+    resolver
+        .build(
+            json!({
+                "1": call("fees"),
+                "2": {
+                    "type": "action",
+                    "name": "set_total",
+                    "definition": {
+                        "type": "set_variable",
+                        "variable": "total",
+                        "value": {
+                            "type": "function",
+                            "function": "add",
+                            "args": [
+                            {
+                                "type": "data",
+                                "path": "/amount"
+                            },
+                            {
+                                "type": "output",
+                                "output_id": "1",
+                                "path": "/fee"
+                            }
+                            ]
+                        }
+                    }
+                }
+            }),
+            json!({
+                "total": {
+                    "type": "variable",
+                    "variable": "total"
+                }
+            }),
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_subworkflow() {
+    let workflow = caller(&resolver());
+
+    workflow.validate().unwrap();
+    assert_eq!(
+        workflow.process(json!({ "amount": 100 })).unwrap(),
+        json!({ "total": 110.0 })
+    );
+}
+
+#[tokio::test]
+async fn test_subworkflow_async() {
+    let workflow = caller(&resolver());
+
+    assert_eq!(
+        workflow
+            .process_async(json!({ "amount": 100 }))
+            .await
+            .unwrap(),
+        json!({ "total": 110.0 })
+    );
+}
+
+#[test]
+fn test_subworkflow_resolver_missing() {
+    let result = Workflow::builder()
+        .with_definition(json!({ "1": call("fees") }))
+        .with_output(json!({}))
+        .build();
+
+    assert_snapshot!(result.unwrap_err().to_string());
+}
+
+#[test]
+fn test_subworkflow_recursion() {
+    let resolver = Resolver::default()
+        .with("a", json!({ "1": call("b") }), json!({}))
+        .with("b", json!({ "1": call("a") }), json!({}));
+    let workflow = resolver
+        .build(json!({ "1": call("a") }), json!({}))
+        .unwrap();
+
+    assert_snapshot!(workflow.validate().unwrap_err().to_string());
+}
+
+#[test]
+fn test_subworkflow_depth_exceeded() {
+    let resolver = (0..4).fold(Resolver::default(), |resolver, level| {
+        resolver.with(
+            &format!("level_{}", level),
+            json!({ "1": call(&format!("level_{}", level + 1)) }),
+            json!({}),
+        )
+    });
+    let resolver = resolver.with("level_4", json!({}), json!({}));
+
+    let workflow = Workflow::builder()
+        .with_definition(json!({ "1": call("level_0") }))
+        .with_output(json!({}))
+        .with_resolver(Arc::new(resolver.to_owned()))
+        .with_max_depth(3)
+        .build()
+        .unwrap();
+    assert_snapshot!(workflow.validate().unwrap_err().to_string());
+
+    let workflow = resolver
+        .build(json!({ "1": call("level_0") }), json!({}))
+        .unwrap();
+    workflow.validate().unwrap();
+}
+
+#[test]
+fn test_subworkflow_components_limit() {
+    let limits = |max_components| Limits {
+        max_components: Some(max_components),
+        ..Default::default()
+    };
+    let workflow = |limits| {
+        Workflow::builder()
+            .with_definition(json!({ "1": call("fees") }))
+            .with_output(json!({}))
+            .with_resolver(Arc::new(resolver()))
+            .with_limits(limits)
+            .build()
+            .unwrap()
+    };
+
+    workflow(limits(2))
+        .process(json!({ "amount": 100 }))
+        .unwrap();
+    assert_eq!(
+        workflow(limits(1))
+            .process(json!({ "amount": 100 }))
+            .unwrap_err()
+            .to_string(),
+        "Processed more than 1 components"
+    );
+}
+
+#[tokio::test]
+async fn test_subworkflow_depth_exceeded_at_runtime() {
+    let resolver = Resolver::default().with("loop", json!({ "1": call("loop") }), json!({}));
+    let workflow = Workflow::builder()
+        .with_definition(json!({ "1": call("loop") }))
+        .with_output(json!({}))
+        .with_resolver(Arc::new(resolver))
+        .with_max_depth(3)
+        .build()
+        .unwrap();
+
+    let message = "Workflow `loop` exceeds the maximum nesting depth of 3";
+    assert_eq!(
+        workflow
+            .process(json!({ "amount": 100 }))
+            .unwrap_err()
+            .to_string(),
+        message
+    );
+    assert_eq!(
+        workflow
+            .process_async(json!({ "amount": 100 }))
+            .await
+            .unwrap_err()
+            .to_string(),
+        message
+    );
+}
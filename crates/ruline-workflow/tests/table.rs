@@ -0,0 +1,254 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use insta::assert_snapshot;
+use ruline_workflow::{LintKind, TraceOutcome, Workflow};
+use serde_json::{json, Value};
+
+fn rate_table(hit_policy: &str) -> Value {
+    json!({
+        "type": "decision_table",
+        "name": "rate",
+        "definition": {
+            "hit_policy": hit_policy,
+            "inputs": [
+                { "name": "age", "field": { "type": "data", "path": "/age" } },
+                {
+                    "name": "country",
+                    "field": { "type": "data", "path": "/country" },
+                    "values": [ "AR", "UY", "BR" ]
+                }
+            ],
+            "outputs": [
+                { "name": "rate", "values": [ 0.3, 0.2, 0.1 ] }
+            ],
+            "rules": [
+                {
+                    "tests": [ [ { "operator": "less_than", "value": 18 } ], [] ],
+                    "outputs": [ 0.3 ]
+                },
+                {
+                    "tests": [
+                        [ { "operator": "greater_than_or_equal", "value": 18 } ],
+                        [ { "operator": "contains", "value": [ "AR", "UY" ] } ]
+                    ],
+                    "outputs": [ 0.1 ]
+                },
+                {
+                    "tests": [
+                        [ { "operator": "greater_than_or_equal", "value": 18 } ],
+                        [ { "operator": "equals", "value": "BR" } ]
+                    ],
+                    "outputs": [ 0.2 ]
+                }
+            ]
+        }
+    })
+}
+
+fn workflow(table: Value) -> Workflow {
+    Workflow::builder()
+        .with_definition(json!({
+            "1": table,
+            "2": {
+                "type": "action",
+                "name": "set_rate",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "rate",
+                    "value": { "type": "output", "output_id": "1", "path": "" }
+                }
+            }
+        }))
+        .with_output(json!({ "rate": { "type": "variable", "variable": "rate" } }))
+        .build()
+        .unwrap()
+}
+
+fn process(workflow: &Workflow, data: Value) -> Value {
+    workflow.process(data).unwrap()["rate"].to_owned()
+}
+
+#[test]
+fn test_table_unique() {
+    let workflow = workflow(rate_table("unique"));
+    workflow.validate().unwrap();
+    assert!(workflow.lint().is_empty());
+
+    assert_eq!(
+        process(&workflow, json!({ "age": 10, "country": "AR" })),
+        json!({ "rate": 0.3 })
+    );
+    assert_eq!(
+        process(&workflow, json!({ "age": 30, "country": "UY" })),
+        json!({ "rate": 0.1 })
+    );
+    assert_eq!(
+        process(&workflow, json!({ "age": 30, "country": "BR" })),
+        json!({ "rate": 0.2 })
+    );
+    assert_eq!(
+        process(&workflow, json!({ "age": 30, "country": "CL" })),
+        Value::Null
+    );
+}
+
+fn overlapping(hit_policy: &str) -> Value {
+    let mut table = rate_table(hit_policy);
+    table["definition"]["rules"][0]["tests"][0] =
+        json!([ { "operator": "less_than_or_equal", "value": 21 } ]);
+    table
+}
+
+#[test]
+fn test_table_unique_overlap() {
+    let workflow = workflow(overlapping("unique"));
+    assert_snapshot!(
+        "table_unique_overlap",
+        workflow.validate().unwrap_err().to_string()
+    );
+    assert_snapshot!(
+        "table_unique_not_unique",
+        workflow
+            .process(json!({ "age": 20, "country": "AR" }))
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[test]
+fn test_table_first_and_priority() {
+    let first = workflow(overlapping("first"));
+    first.validate().unwrap();
+    assert_eq!(
+        process(&first, json!({ "age": 20, "country": "AR" })),
+        json!({ "rate": 0.3 })
+    );
+
+    let mut table = overlapping("priority");
+    table["definition"]["outputs"][0]["values"] = json!([0.1, 0.2, 0.3]);
+    let priority = workflow(table);
+    priority.validate().unwrap();
+    assert_eq!(
+        process(&priority, json!({ "age": 20, "country": "AR" })),
+        json!({ "rate": 0.1 })
+    );
+    assert_eq!(
+        process(&priority, json!({ "age": 20, "country": "CL" })),
+        json!({ "rate": 0.3 })
+    );
+}
+
+#[test]
+fn test_table_collect() {
+    let data = json!({ "age": 20, "country": "UY" });
+
+    let collect = workflow(overlapping("collect"));
+    assert_eq!(
+        process(&collect, data.to_owned()),
+        json!([ { "rate": 0.3 }, { "rate": 0.1 } ])
+    );
+
+    let cases = [
+        ("collect_sum", json!(0.4)),
+        ("collect_min", json!(0.1)),
+        ("collect_max", json!(0.3)),
+        ("collect_count", json!(2)),
+    ];
+    for (hit_policy, expected) in cases {
+        let workflow = workflow(overlapping(hit_policy));
+        assert_eq!(
+            process(&workflow, data.to_owned()),
+            expected,
+            "{}",
+            hit_policy
+        );
+    }
+
+    let none = workflow(overlapping("collect_sum"));
+    assert_eq!(
+        process(&none, json!({ "age": 30, "country": "CL" })),
+        Value::Null
+    );
+    let none = workflow(overlapping("collect_count"));
+    assert_eq!(
+        process(&none, json!({ "age": 30, "country": "CL" })),
+        json!(0)
+    );
+}
+
+#[test]
+fn test_table_gap() {
+    let mut table = rate_table("unique");
+    table["definition"]["inputs"][1]["values"] = json!([]);
+    let warnings = workflow(table).lint();
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, LintKind::IncompleteDecisionTable);
+    assert_snapshot!("table_gap", warnings[0].to_string());
+}
+
+#[test]
+fn test_table_incomplete() {
+    let incomplete = |hit_policy: &str| {
+        let mut table = rate_table(hit_policy);
+        table["definition"]["inputs"][1]["values"] = json!([]);
+        workflow(table)
+    };
+
+    assert_snapshot!(
+        "table_incomplete",
+        incomplete("first").validate().unwrap_err().to_string()
+    );
+    incomplete("collect").validate().unwrap();
+
+    let mut table = rate_table("first");
+    table["definition"]["inputs"][1]["values"] = json!([]);
+    table["definition"]["rules"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({ "tests": [ [], [] ], "outputs": [ 0.3 ] }));
+    workflow(table).validate().unwrap();
+}
+
+#[test]
+fn test_table_trace() {
+    let (_, trace) = workflow(overlapping("collect"))
+        .process_with_trace(json!({ "age": 20, "country": "UY" }))
+        .unwrap();
+
+    let TraceOutcome::DecisionTable { rules, output } = &trace.steps[0].outcome else {
+        panic!("expected a decision table outcome");
+    };
+    assert_eq!(rules, &vec![1, 2]);
+    assert_eq!(output, &json!([ { "rate": 0.3 }, { "rate": 0.1 } ]));
+}
+
+#[test]
+fn test_table_invalid() {
+    let build = |table: Value| {
+        Workflow::builder()
+            .with_definition(json!({ "1": table }))
+            .with_output(json!({}))
+            .build()
+            .unwrap_err()
+            .to_string()
+    };
+
+    let mut table = rate_table("unique");
+    table["definition"]["rules"][1]["tests"] = json!([[]]);
+    assert_snapshot!("table_invalid_tests", build(table));
+
+    let mut table = rate_table("priority");
+    table["definition"]["rules"][1]["outputs"] = json!([0.5]);
+    assert_snapshot!("table_invalid_priority", build(table));
+
+    let mut table = rate_table("collect_sum");
+    table["definition"]["rules"][1]["outputs"] = json!(["high"]);
+    assert_snapshot!("table_invalid_sum", build(table));
+
+    let mut table = rate_table("unique");
+    table["definition"]["rules"][0]["tests"][0] = json!([ { "operator": "exists", "value": 1 } ]);
+    assert_snapshot!("table_invalid_unary", build(table));
+}
@@ -0,0 +1,156 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use ruline_workflow::{TraceOutcome, TraceStep, Workflow};
+use serde_json::{json, Value};
+
+fn workflow() -> Workflow {
+    let definition = json!({
+        "1": {
+            "type": "condition",
+            "name": "is_adult",
+            "definition": {
+                "type": "binary",
+                "fallbacks": [ "3" ],
+                "results": [ "2" ],
+                "expression": {
+                    "id": "100",
+                    "type": "comparison",
+                    "operator": "greater_than_or_equal",
+                    "operands": [
+                    {
+                        "type": "data",
+                        "path": "/age"
+                    },
+                    {
+                        "type": "value",
+                        "value": 18
+                    }
+                    ]
+                }
+            }
+        },
+        "2": {
+            "type": "action",
+            "name": "approve",
+            "definition": {
+                "type": "set_variable",
+                "variable": "decision",
+                "value": {
+                    "type": "value",
+                    "value": "approved"
+                }
+            }
+        },
+        "3": {
+            "type": "action",
+            "name": "reject",
+            "definition": {
+                "type": "set_variable",
+                "variable": "decision",
+                "value": {
+                    "type": "value",
+                    "value": "rejected"
+                }
+            }
+        }
+    });
+
+    let output = json!({
+        "decision": {
+            "type": "variable",
+            "variable": "decision"
+        }
+    });
+
+    Workflow::builder()
+        .with_definition(definition)
+        .with_output(output)
+        .build()
+        .unwrap()
+}
+
+fn summary(step: &TraceStep) -> Value {
+    let mut summary = serde_json::to_value(step).unwrap();
+    summary.as_object_mut().unwrap().remove("elapsed");
+    summary
+}
+
+#[test]
+fn test_process_with_trace() {
+    let workflow = workflow();
+
+    let (result, trace) = workflow.process_with_trace(json!({ "age": 30 })).unwrap();
+    assert_eq!(result, json!({ "decision": "approved" }));
+    assert_eq!(
+        trace.steps.iter().map(summary).collect::<Vec<_>>(),
+        vec![
+            json!({
+                "component_id": "1",
+                "name": "is_adult",
+                "outcome": {
+                    "type": "condition",
+                    "branch": "results",
+                    "matched": [ "100" ],
+                    "comparisons": [
+                    {
+                        "id": "100",
+                        "operator": "greater_than_or_equal",
+                        "operands": [ 30, 18 ],
+                        "result": true
+                    }
+                    ]
+                },
+                "skipped": [ "3" ]
+            }),
+            json!({
+                "component_id": "2",
+                "name": "approve",
+                "outcome": {
+                    "type": "action",
+                    "variable": "decision",
+                    "value": "approved"
+                },
+                "skipped": []
+            }),
+        ]
+    );
+    assert!(trace.elapsed >= trace.steps.iter().map(|step| step.elapsed).sum());
+}
+
+#[test]
+fn test_process_with_trace_fallback() {
+    let workflow = workflow();
+
+    let (result, trace) = workflow.process_with_trace(json!({ "age": 12 })).unwrap();
+    assert_eq!(result, json!({ "decision": "rejected" }));
+    assert_eq!(
+        trace
+            .steps
+            .iter()
+            .map(|step| step.component_id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["1", "3"]
+    );
+    assert_eq!(trace.steps[0].skipped, vec!["2"]);
+
+    match &trace.steps[0].outcome {
+        TraceOutcome::Condition(condition) => {
+            assert!(condition.matched.is_empty());
+            assert!(!condition.comparisons[0].result);
+        }
+        outcome => panic!("unexpected outcome {:?}", outcome),
+    }
+}
+
+#[test]
+fn test_process_with_trace_matches_process() {
+    let workflow = workflow();
+
+    for age in [12, 18, 30] {
+        let data = json!({ "age": age });
+        let (result, _) = workflow.process_with_trace(data.to_owned()).unwrap();
+        assert_eq!(result, workflow.process(data).unwrap());
+    }
+}
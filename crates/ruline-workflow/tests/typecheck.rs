@@ -0,0 +1,149 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use insta::assert_snapshot;
+use ruline_workflow::{TypeMismatch, Workflow};
+use serde_json::{json, Value};
+
+fn comparison(operator: &str, operands: Value) -> Value {
+    json!({
+        "type": "condition",
+        "name": "check",
+        "definition": {
+            "type": "binary",
+            "fallbacks": [],
+            "results": [],
+            "expression": {
+                "id": "100",
+                "type": "comparison",
+                "operator": operator,
+                "operands": operands
+            }
+        }
+    })
+}
+
+fn workflow(definition: Value, schema: Value) -> Workflow {
+    Workflow::builder()
+        .with_definition(definition)
+        .with_output(json!({}))
+        .with_schema(schema)
+        .with_variables(json!({
+            "score": { "type": "number", "value": 0 }
+        }))
+        .build()
+        .unwrap()
+}
+
+fn schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "name": { "type": "string" },
+            "age": { "type": "integer" },
+            "tags": { "type": "array", "items": { "type": "string" } }
+        }
+    })
+}
+
+#[test]
+fn test_check_types() {
+    let workflow = workflow(
+        json!({
+            "1": comparison("greater_than", json!([
+                { "type": "data", "path": "/age" },
+                { "type": "variable", "variable": "score" }
+            ])),
+            "2": comparison("contains", json!([
+                { "type": "data", "path": "/name" },
+                { "type": "data", "path": "/tags" }
+            ])),
+            "3": comparison("empty", json!([
+                { "type": "data", "path": "/tags/0" }
+            ]))
+        }),
+        schema(),
+    );
+
+    assert!(workflow.check_types().is_empty());
+    workflow.validate().unwrap();
+}
+
+#[test]
+fn test_check_types_comparison_mismatch() {
+    let workflow = workflow(
+        json!({
+            "1": comparison("greater_than", json!([
+                { "type": "data", "path": "/name" },
+                { "type": "value", "value": 18 }
+            ]))
+        }),
+        schema(),
+    );
+
+    assert_eq!(
+        workflow.check_types(),
+        vec![TypeMismatch {
+            component_id: "1".to_owned(),
+            expression_id: Some("100".to_owned()),
+            message: "`greater_than` cannot compare operands of type `string`, `number`".to_owned(),
+        }]
+    );
+}
+
+#[test]
+fn test_check_types_without_schema() {
+    let workflow = workflow(
+        json!({
+            "1": comparison("greater_than", json!([
+                { "type": "data", "path": "/name" },
+                { "type": "value", "value": 18 }
+            ]))
+        }),
+        Value::Null,
+    );
+
+    assert!(workflow.check_types().is_empty());
+}
+
+#[test]
+fn test_check_types_function() {
+    let workflow = workflow(
+        json!({
+            "1": comparison("equals", json!([
+                {
+                    "type": "function",
+                    "function": "upper",
+                    "args": [{ "type": "data", "path": "/age" }]
+                },
+                { "type": "value", "value": "JOHN" }
+            ])),
+            "2": {
+                "type": "action",
+                "name": "set_score",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "score",
+                    "value": {
+                        "type": "function",
+                        "function": "mod",
+                        "args": [{ "type": "value", "value": 10 }]
+                    }
+                }
+            },
+            "3": {
+                "type": "action",
+                "name": "set_score_name",
+                "definition": {
+                    "type": "set_variable",
+                    "variable": "score",
+                    "value": { "type": "data", "path": "/name" }
+                }
+            }
+        }),
+        schema(),
+    );
+
+    assert_snapshot!(workflow.validate().unwrap_err().to_string());
+}
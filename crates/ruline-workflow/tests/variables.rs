@@ -0,0 +1,234 @@
+#[cfg(test)]
+#[macro_use]
+extern crate pretty_assertions;
+
+use std::{sync::Arc, thread};
+
+use insta::assert_snapshot;
+use ruline_workflow::Workflow;
+use serde_json::{json, Value};
+
+fn definition() -> Value {
+    json!({
+        "1": {
+            "type": "condition",
+            "name": "is_flagged",
+            "definition": {
+                "type": "binary",
+                "fallbacks": [],
+                "results": [ "2" ],
+                "expression": {
+                    "id": "100",
+                    "type": "comparison",
+                    "operator": "equals",
+                    "operands": [
+                    {
+                        "type": "data",
+                        "path": "/flagged"
+                    },
+                    {
+                        "type": "value",
+                        "value": true
+                    }
+                    ]
+                }
+            }
+        },
+        "2": {
+            "type": "action",
+            "name": "set_color",
+            "definition": {
+                "type": "set_variable",
+                "variable": "color",
+                "value": {
+                    "type": "data",
+                    "path": "/color"
+                }
+            }
+        },
+        "3": {
+            "type": "action",
+            "name": "increment_counter",
+            "definition": {
+                "type": "set_variable",
+                "variable": "counter",
+                "value": {
+                    "type": "function",
+                    "function": "add",
+                    "args": [
+                    {
+                        "type": "variable",
+                        "variable": "counter"
+                    },
+                    {
+                        "type": "data",
+                        "path": "/increment"
+                    }
+                    ]
+                }
+            }
+        }
+    })
+}
+
+fn output() -> Value {
+    json!({
+        "color": {
+            "type": "variable",
+            "variable": "color"
+        },
+        "counter": {
+            "type": "variable",
+            "variable": "counter"
+        }
+    })
+}
+
+fn variables() -> Value {
+    json!({
+        "color": {
+            "type": "string",
+            "value": "none"
+        },
+        "counter": {
+            "type": "number",
+            "value": 10
+        },
+        "tags": {
+            "type": "array"
+        }
+    })
+}
+
+fn workflow() -> Workflow {
+    Workflow::builder()
+        .with_definition(definition())
+        .with_output(output())
+        .with_variables(variables())
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_variables_initial_values() {
+    let workflow = workflow();
+
+    let result = workflow
+        .process(json!({ "flagged": false, "increment": 1 }))
+        .unwrap();
+    assert_eq!(result, json!({ "color": "none", "counter": 11.0 }));
+}
+
+#[test]
+fn test_variables_isolated_between_runs() {
+    let workflow = workflow();
+
+    let result = workflow
+        .process(json!({ "flagged": true, "color": "red", "increment": 5 }))
+        .unwrap();
+    assert_eq!(result, json!({ "color": "red", "counter": 15.0 }));
+
+    let result = workflow
+        .process(json!({ "flagged": false, "increment": 1 }))
+        .unwrap();
+    assert_eq!(result, json!({ "color": "none", "counter": 11.0 }));
+}
+
+#[test]
+fn test_variables_concurrent_executions() {
+    let workflow = Arc::new(workflow());
+
+    let handles = (0..16)
+        .map(|i| {
+            let workflow = Arc::clone(&workflow);
+            thread::spawn(move || {
+                (0..50)
+                    .map(|_| {
+                        let data = json!({
+                            "flagged": i % 2 == 0,
+                            "color": format!("color_{}", i),
+                            "increment": i,
+                        });
+                        workflow.process(data).unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (i, handle) in handles.into_iter().enumerate() {
+        let color = match i % 2 == 0 {
+            true => json!(format!("color_{}", i)),
+            false => json!("none"),
+        };
+
+        for result in handle.join().unwrap() {
+            assert_eq!(
+                result,
+                json!({ "color": color, "counter": 10.0 + i as f64 })
+            );
+        }
+    }
+}
+
+#[test]
+fn test_workflow_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Workflow>();
+}
+
+#[test]
+fn test_variables_type_mismatch() {
+    assert_snapshot!(Workflow::builder()
+        .with_definition(definition())
+        .with_output(output())
+        .with_variables(json!({
+            "counter": {
+                "type": "number",
+                "value": "ten"
+            }
+        }))
+        .build()
+        .unwrap_err()
+        .to_string());
+}
+
+#[test]
+fn test_variables_malformed() {
+    assert_snapshot!(Workflow::builder()
+        .with_definition(definition())
+        .with_output(output())
+        .with_variables(json!({
+            "counter": {
+                "type": "integer",
+                "value": 10
+            }
+        }))
+        .build()
+        .unwrap_err()
+        .to_string());
+}
+
+#[test]
+fn test_variables_write_type_mismatch() {
+    let workflow = workflow();
+
+    let err = workflow
+        .process(json!({ "flagged": true, "color": 7, "increment": 1 }))
+        .unwrap_err();
+    assert_snapshot!(err.to_string());
+
+    let workflow = Workflow::builder()
+        .with_definition(definition())
+        .with_output(output())
+        .with_variables(json!({
+            "color": { "type": "any", "value": "none" },
+            "counter": { "type": "number", "value": 10 }
+        }))
+        .build()
+        .unwrap();
+    let result = workflow
+        .process(json!({ "flagged": true, "color": 7, "increment": 1 }))
+        .unwrap();
+    assert_eq!(result["color"], json!(7));
+}